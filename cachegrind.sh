@@ -0,0 +1,9 @@
+#!/bin/bash
+# Count instructions and cache misses of the benchmarks under Cachegrind (requires valgrind)
+
+set -e
+
+SCRIPT_DIR="$(dirname "$0")"
+
+cd "$SCRIPT_DIR"
+cargo run --release -p vello_bench_core --bin cachegrind -- "$@"
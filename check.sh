@@ -0,0 +1,25 @@
+#!/bin/bash
+# Build, lint and test the workspace (arguments are passed to cargo, e.g. `--exclude vello_bench_tauri`).
+# Without a Vello checkout at ../vello, checks against the published vello_common and vello_cpu
+# (VELLO_VERSION, default 0.0.6) in a copy of the workspace instead.
+
+set -e
+
+cd "$(dirname "$0")"
+
+VELLO_VERSION="${VELLO_VERSION:-0.0.6}"
+
+if [ ! -d ../vello/sparse_strips ]; then
+    echo "No Vello checkout at ../vello, checking against vello $VELLO_VERSION from crates.io"
+    WORKSPACE="$(pwd)"
+    COPY="$(mktemp -d)"
+    trap 'rm -rf "$COPY"' EXIT
+    tar cf - --exclude=./target --exclude=./.git . | tar xf - -C "$COPY"
+    sed -i "s|^vello_common = { path = .*|vello_common = \"$VELLO_VERSION\"|; s|^vello_cpu = { path = .*|vello_cpu = \"$VELLO_VERSION\"|" "$COPY/Cargo.toml"
+    export CARGO_TARGET_DIR="$WORKSPACE/target/published-vello"
+    cd "$COPY"
+fi
+
+cargo build --workspace "$@"
+cargo clippy --workspace --all-targets "$@" -- -D warnings
+cargo test --workspace "$@"
@@ -1,7 +1,9 @@
 // Vello Benchmark Suite - Web UI
 
+const DEFAULT_WARMUP_MS = 50;
 const DEFAULT_CALIBRATION_MS = 100;
 const DEFAULT_MEASUREMENT_MS = 250;
+const DEFAULT_CONFIDENCE_LEVEL = 0.95;
 
 const state = {
     benchmarks: [],
@@ -16,13 +18,24 @@ const state = {
     abortRequested: false,
     isTauri: false,
     wasmWorker: null,
-    wasmSimdLevel: 'scalar',
     wasmSimd128Available: false,
     executionMode: 'native',
     pendingWasmResolve: null,
     references: [],
     loadedReference: null,
     referenceResults: new Map(),
+    // Comparisons against the reference by benchmark id, computed by vello_bench_core.
+    comparisons: new Map(),
+    // Why results without a comparison couldn't be compared, by benchmark id.
+    unmatched: new Map(),
+    // Ids matched by the selection filter, or null to show everything.
+    selectionIds: null,
+    // Why the last run of a benchmark failed, by benchmark id.
+    errors: new Map(),
+    // `LevelMatrix` of the last "All levels" run, or null.
+    levelMatrix: null,
+    environment: null,
+    referenceEnvironment: null,
 };
 
 function detectTauri() {
@@ -47,7 +60,7 @@ function createWasmWorker() {
 
         switch (type) {
             case 'result':
-                state.pendingWasmResolve(data.result);
+                state.pendingWasmResolve(data);
                 state.pendingWasmResolve = null;
                 break;
             case 'error':
@@ -55,10 +68,31 @@ function createWasmWorker() {
                 state.pendingWasmResolve(null);
                 state.pendingWasmResolve = null;
                 break;
+            case 'levels':
+                state.pendingWasmResolve(data.levels);
+                state.pendingWasmResolve = null;
+                break;
             case 'benchmarks':
                 state.pendingWasmResolve(data.benchmarks);
                 state.pendingWasmResolve = null;
                 break;
+            case 'environment':
+                state.pendingWasmResolve(data.environment);
+                state.pendingWasmResolve = null;
+                break;
+            case 'comparisons':
+                state.pendingWasmResolve(data.comparisons);
+                state.pendingWasmResolve = null;
+                break;
+            case 'matrix':
+                state.pendingWasmResolve(data.matrix);
+                state.pendingWasmResolve = null;
+                break;
+            case 'selection':
+            case 'validation':
+                state.pendingWasmResolve(data);
+                state.pendingWasmResolve = null;
+                break;
         }
     };
 
@@ -92,28 +126,18 @@ async function checkSimd128Available() {
     }
 }
 
+// The SIMD128 build runs both its SIMD128 and scalar paths (see `get_simd_levels`), so the
+// scalar build is only needed where SIMD128 isn't available.
 async function loadWasm() {
     state.wasmSimd128Available = await checkSimd128Available();
     const pkgDir = state.wasmSimd128Available ? 'pkg-simd' : 'pkg';
-    state.wasmSimdLevel = state.wasmSimd128Available ? 'simd128' : 'scalar';
     return await loadWasmFrom(pkgDir);
 }
 
-async function switchWasmSimdLevel(level) {
-    if (level === state.wasmSimdLevel) return true;
-
-    const pkgDir = level === 'simd128' ? 'pkg-simd' : 'pkg';
-    const success = await loadWasmFrom(pkgDir);
-    if (success) {
-        state.wasmSimdLevel = level;
-        await loadBenchmarks();
-    }
-    return success;
-}
-
 async function init() {
     state.isTauri = detectTauri();
 
+    document.getElementById('warmup-ms').value = DEFAULT_WARMUP_MS;
     document.getElementById('calibration-ms').value = DEFAULT_CALIBRATION_MS;
     document.getElementById('measurement-ms').value = DEFAULT_MEASUREMENT_MS;
 
@@ -135,11 +159,12 @@ async function init() {
 
     if (!state.isTauri && !wasmLoaded) {
         document.getElementById('benchmark-tbody').innerHTML =
-            '<tr><td colspan="7" class="no-results">Failed to load WASM module. Build it with: ./scripts/build-wasm.sh</td></tr>';
+            '<tr><td colspan="10" class="no-results">Failed to load WASM module. Build it with: ./scripts/build-wasm.sh</td></tr>';
         return;
     }
 
     await loadSimdLevels();
+    await loadTimers();
     await loadBenchmarks();
     await loadReferencesList();
     setupEventListeners();
@@ -150,26 +175,38 @@ async function loadSimdLevels() {
         let levels;
         if (state.executionMode === 'native' && state.isTauri) {
             levels = await invoke('get_simd_levels');
+        } else if (state.wasmWorker) {
+            levels = await new Promise((resolve) => {
+                state.pendingWasmResolve = resolve;
+                state.wasmWorker.postMessage({ type: 'levels' });
+            });
         } else {
-            levels = [{ id: 'scalar', name: 'Scalar' }];
-            if (state.wasmSimd128Available) {
-                levels.unshift({ id: 'simd128', name: 'SIMD128' });
-            }
+            levels = [];
         }
 
         const select = document.getElementById('simd-level');
         select.innerHTML = levels.map(l =>
             `<option value="${l.id}">${l.name}</option>`
         ).join('');
-
-        if (state.executionMode === 'wasm') {
-            select.value = state.wasmSimdLevel;
-        }
     } catch (e) {
         console.error('Failed to load SIMD levels:', e);
     }
 }
 
+async function loadTimers() {
+    const select = document.getElementById('timer');
+    if (state.executionMode === 'native' && state.isTauri) {
+        try {
+            const timers = await invoke('get_timers');
+            select.innerHTML = timers.map(t => `<option value="${t.id}">${t.name}</option>`).join('');
+        } catch (e) {
+            console.error('Failed to load timers:', e);
+        }
+    } else {
+        select.innerHTML = '<option value="">performance.now()</option>';
+    }
+}
+
 async function loadBenchmarks() {
     try {
         if (state.executionMode === 'native' && state.isTauri) {
@@ -274,13 +311,53 @@ function getCategorySet() {
 }
 
 function getFilteredBenchmarks() {
-    if (state.currentCategory === 'all') return state.benchmarks;
-    return state.benchmarks.filter(b =>
+    const selected = state.selectionIds
+        ? state.benchmarks.filter(b => state.selectionIds.has(b.id))
+        : state.benchmarks;
+    if (state.currentCategory === 'all') return selected;
+    return selected.filter(b =>
         b.category === state.currentCategory ||
         b.category.startsWith(state.currentCategory + '/')
     );
 }
 
+// The selection syntax is parsed by vello_bench_core, so every host selects the same
+// benchmarks for the same string.
+async function selectBenchmarks(spec) {
+    if (state.executionMode === 'native' && state.isTauri) {
+        return await invoke('select_benchmarks', { selection: spec });
+    } else if (state.wasmWorker && !state.pendingWasmResolve) {
+        const { benchmarks, error } = await new Promise((resolve) => {
+            state.pendingWasmResolve = resolve;
+            state.wasmWorker.postMessage({ type: 'select', selection: spec });
+        });
+        if (error) throw new Error(error);
+        return benchmarks;
+    }
+    return null;
+}
+
+async function applySelection() {
+    const input = document.getElementById('selection');
+    const spec = input.value.trim();
+    input.classList.remove('invalid');
+
+    let selectionIds = null;
+    if (spec) {
+        try {
+            const benchmarks = await selectBenchmarks(spec);
+            selectionIds = benchmarks ? new Set(benchmarks.map(b => b.id)) : null;
+        } catch (e) {
+            input.classList.add('invalid');
+            console.error('Invalid selection:', e);
+            return;
+        }
+    }
+
+    state.selectionIds = selectionIds;
+    renderBenchmarks();
+}
+
 function renderBenchmarks() {
     const tbody = document.getElementById('benchmark-tbody');
     const filtered = getFilteredBenchmarks();
@@ -292,7 +369,7 @@ function renderBenchmarks() {
     }
 
     if (filtered.length === 0) {
-        tbody.innerHTML = '<tr><td colspan="7" class="no-results">No benchmarks available.</td></tr>';
+        tbody.innerHTML = '<tr><td colspan="10" class="no-results">No benchmarks available.</td></tr>';
         return;
     }
 
@@ -309,6 +386,12 @@ function renderBenchmarks() {
         } else if (state.queuedBenchmarks.has(bench.id)) {
             status = 'queued';
             statusText = 'queued';
+        } else if (state.errors.has(bench.id)) {
+            status = 'failed';
+            statusText = 'failed';
+        } else if (result && (result.stop_reason === 'timed_out' || result.stop_reason === 'truncated')) {
+            status = 'truncated';
+            statusText = result.stop_reason === 'timed_out' ? 'timed out' : 'truncated';
         } else if (result) {
             status = 'completed';
             statusText = 'done';
@@ -317,6 +400,27 @@ function renderBenchmarks() {
         const meanStr = result
             ? (() => { const { mean, unit } = formatTime(result.statistics.mean_ns); return `${mean.toFixed(3)} ${unit}`; })()
             : '-';
+        const spreadStr = result ? formatSpread(result.statistics) : '-';
+        const throughputStr = result?.throughput ? formatThroughput(result.throughput) : '-';
+        const throughputTitle = result?.throughput
+            ? `${result.throughput.per_iteration} ${result.throughput.unit}/iter, ${formatNs(result.throughput.ns_per_unit)}/${THROUGHPUT_UNITS[result.throughput.unit] ?? result.throughput.unit}`
+            : '';
+        const allocStr = result?.allocations ? formatAllocations(result.allocations) : '-';
+        const allocTitle = result?.allocations
+            ? `${result.allocations.allocations.toFixed(1)} allocs, ${result.allocations.deallocations.toFixed(1)} deallocs, ${formatBytes(result.allocations.bytes_allocated)} per iteration`
+            : '';
+        const spreadTitle = result
+            ? [
+                formatDistribution(result.statistics),
+                formatOutliers(result.outliers),
+                formatStopReason(result.stop_reason),
+                formatDiagnostics(result.diagnostics),
+                formatCounters(result.counters),
+                result.setup_mean_ns != null ? `untimed setup: ${formatNs(result.setup_mean_ns)}/iter` : '',
+                result.pinned_core != null ? `pinned to core ${result.pinned_core}` : '',
+                result.nice != null ? `nice: ${result.nice}` : '',
+            ].filter(Boolean).join('\n')
+            : '';
 
         let refStr = '-';
         let changeStr = '-';
@@ -327,22 +431,26 @@ function renderBenchmarks() {
             refStr = `${mean.toFixed(3)} ${unit}`;
         }
 
-        if (result && refResult) {
-            const comparison = calculateComparison(result.statistics.mean_ns, refResult.statistics.mean_ns);
-            if (comparison) {
-                const sign = comparison.percentChange > 0 ? '+' : '';
-                changeStr = `${sign}${comparison.percentChange.toFixed(1)}%`;
-
-                if (comparison.status === 'faster') {
-                    changeClass = 'change-faster';
-                    changeStr += ` (${comparison.speedup.toFixed(2)}x)`;
-                } else if (comparison.status === 'slower') {
-                    changeClass = 'change-slower';
-                    changeStr += ` (${(1/comparison.speedup).toFixed(2)}x)`;
-                } else {
-                    changeClass = 'change-similar';
-                }
+        const comparison = result && refResult ? state.comparisons.get(bench.id) : null;
+        let changeTitle = '';
+        if (comparison) {
+            const percentChange = Math.abs(comparison.percent_change) < 0.005 ? 0 : comparison.percent_change;
+            const sign = percentChange > 0 ? '+' : '';
+            changeStr = `${sign}${percentChange.toFixed(1)}%`;
+            changeTitle = formatComparison(comparison);
+
+            if (comparison.verdict === 'faster') {
+                changeClass = 'change-faster';
+                changeStr += ` (${(1 / comparison.ratio).toFixed(2)}x)`;
+            } else if (comparison.verdict === 'slower') {
+                changeClass = 'change-slower';
+                changeStr += ` (${comparison.ratio.toFixed(2)}x)`;
+            } else {
+                changeClass = 'change-similar';
             }
+        } else if (result && refResult && state.unmatched.get(bench.id) === 'params_differ') {
+            changeStr = 'params differ';
+            changeTitle = 'The reference ran with different parameters';
         }
 
         const rowClasses = [status];
@@ -353,17 +461,26 @@ function renderBenchmarks() {
                 <td class="col-select">
                     <input type="checkbox" class="row-checkbox" ${isSelected ? 'checked' : ''} ${state.isRunning ? 'disabled' : ''}>
                 </td>
-                <td class="col-name">${bench.name}</td>
+                <td class="col-name" title="${escapeHtml([bench.timed, formatParams(bench.params, result?.params)].filter(Boolean).join('\n\n'))}">${bench.name}${bench.level_agnostic ? LEVEL_AGNOSTIC_LABEL : ''}</td>
                 <td class="col-category">${bench.category}</td>
-                <td class="col-status"><span class="status-badge ${status}">${statusText}</span></td>
+                <td class="col-status" title="${escapeHtml(state.errors.get(bench.id) ?? '')}"><span class="status-badge ${status}">${statusText}</span></td>
                 <td class="col-mean"><span class="result-mean">${meanStr}</span></td>
+                <td class="col-spread" title="${spreadTitle}"><span class="result-spread">${spreadStr}</span></td>
+                <td class="col-throughput" title="${throughputTitle}"><span class="result-throughput">${throughputStr}</span></td>
+                <td class="col-allocs" title="${allocTitle}"><span class="result-allocs">${allocStr}</span></td>
                 <td class="col-ref"><span class="result-ref">${refStr}</span></td>
-                <td class="col-change"><span class="result-change ${changeClass}">${changeStr}</span></td>
+                <td class="col-change" title="${changeTitle}"><span class="result-change ${changeClass}">${changeStr}</span></td>
             </tr>
         `;
     }).join('');
 }
 
+const LEVEL_AGNOSTIC_LABEL = ' <span class="level-agnostic" title="Runs the same code at every SIMD level">any level</span>';
+
+function escapeHtml(text) {
+    return text.replace(/[&<>"']/g, c => `&#${c.charCodeAt(0)};`);
+}
+
 function formatTime(meanNs) {
     if (meanNs >= 1_000_000_000) {
         return { mean: meanNs / 1_000_000_000, unit: 's' };
@@ -376,30 +493,235 @@ function formatTime(meanNs) {
     }
 }
 
+function formatNs(ns) {
+    const { mean, unit } = formatTime(ns);
+    return `${mean.toFixed(3)} ${unit}`;
+}
+
+const THROUGHPUT_UNITS = { pixels: 'pix', lines: 'lines', segments: 'seg', strips: 'strips' };
+
+function formatThroughput(throughput) {
+    const unit = THROUGHPUT_UNITS[throughput.unit] ?? throughput.unit;
+    let value = throughput.units_per_second;
+    for (const prefix of ['', 'K', 'M', 'G']) {
+        if (value < 1000 || prefix === 'G') {
+            return `${value.toFixed(2)} ${prefix}${unit}/s`;
+        }
+        value /= 1000;
+    }
+}
+
+function formatBytes(bytes) {
+    let value = bytes;
+    for (const unit of ['B', 'KiB', 'MiB']) {
+        if (value < 1024 || unit === 'MiB') {
+            return unit === 'B' ? `${value.toFixed(0)} B` : `${value.toFixed(1)} ${unit}`;
+        }
+        value /= 1024;
+    }
+}
+
+function formatAllocations(allocations) {
+    return `${allocations.allocations.toFixed(1)} / ${formatBytes(allocations.bytes_allocated)}`;
+}
+
+function formatSpread(stats) {
+    if (!stats.mean_ns || stats.sample_count === undefined) return '-';
+    return `\u00b1${(stats.std_dev_ns / stats.mean_ns * 100).toFixed(1)}%`;
+}
+
+function formatDistribution(stats) {
+    if (stats.sample_count === undefined) return '';
+    return [
+        `median: ${formatNs(stats.median_ns)}`,
+        `std dev: ${formatNs(stats.std_dev_ns)}`,
+        `MAD: ${formatNs(stats.mad_ns)}`,
+        `min: ${formatNs(stats.min_ns)}`,
+        `max: ${formatNs(stats.max_ns)}`,
+        `p5: ${formatNs(stats.p5_ns)}`,
+        `p95: ${formatNs(stats.p95_ns)}`,
+        ...formatInterval('mean', stats.mean_ci),
+        ...formatInterval('median', stats.median_ci),
+        `${stats.sample_count} samples, ${stats.iterations} iterations`,
+    ].join('\n');
+}
+
+function formatStopReason(reason) {
+    switch (reason) {
+        case 'target_precision': return 'stopped: target precision reached';
+        case 'time_budget': return 'stopped: time budget exhausted';
+        case 'truncated': return 'stopped: iteration limit reached';
+        case 'timed_out': return 'stopped: timed out';
+        case 'iteration_count': return 'stopped: fixed iteration count';
+        default: return '';
+    }
+}
+
+function formatInterval(label, ci) {
+    if (!ci || !ci.level) return [];
+    return [`${label} ${Math.round(ci.level * 100)}% CI: ${formatNs(ci.lower_ns)} \u2013 ${formatNs(ci.upper_ns)}`];
+}
+
+function formatOutliers(outliers) {
+    if (!outliers) return '';
+    const total = outliers.low_severe + outliers.low_mild + outliers.high_mild + outliers.high_severe;
+    if (total === 0) return 'no outliers';
+    const parts = [
+        `${outliers.low_severe} low severe`,
+        `${outliers.low_mild} low mild`,
+        `${outliers.high_mild} high mild`,
+        `${outliers.high_severe} high severe`,
+    ];
+    return `${total} outliers${outliers.excluded ? ' (excluded)' : ''}: ${parts.join(', ')}`;
+}
+
 function updateStats() {
     document.getElementById('bench-count').textContent =
         `${state.benchmarks.length} benchmarks`;
-    document.getElementById('bench-completed').textContent =
-        `${state.results.size} completed`;
+    const completed = document.getElementById('bench-completed');
+    completed.textContent = `${state.results.size} completed`;
+    completed.title = formatEnvironment(state.environment);
+}
+
+function formatEnvironment(env) {
+    if (!env) return '';
+    const runner = env.runner ?? {};
+    return [
+        env.cpu_model ? `CPU: ${env.cpu_model} (${env.logical_cores} cores)` : `cores: ${env.logical_cores}`,
+        `OS: ${env.os}${env.kernel ? ` ${env.kernel}` : ''} (${env.arch})`,
+        env.user_agent ? `browser: ${env.user_agent}` : '',
+        `build: ${env.build_profile}${env.simd128 ? ', simd128' : ''}${env.alloc_tracking ? ', alloc-tracking' : ''}`,
+        env.rustc_version ?? '',
+        `vello: ${env.vello_revision ?? 'unknown revision'}`,
+        `runner: ${runner.warmup_ms}/${runner.calibration_ms}/${runner.measurement_ms} ms, ${runner.timer} timer`,
+        ...(env.noise_warnings ?? []).map(w => `warning: ${w.message}`),
+    ].filter(Boolean).join('\n');
+}
+
+async function getEnvironment() {
+    const { calibrationMs, measurementMs, options } = getTimingConfig();
+
+    if (state.executionMode === 'native' && state.isTauri) {
+        return await invoke('get_environment', { calibrationMs, measurementMs, options });
+    } else if (state.wasmWorker) {
+        return new Promise((resolve) => {
+            state.pendingWasmResolve = resolve;
+            state.wasmWorker.postMessage({ type: 'environment', calibrationMs, measurementMs, options });
+        });
+    }
+    return null;
 }
 
 function getTimingConfig() {
     const calibrationMs = Math.max(100, parseInt(document.getElementById('calibration-ms').value) || DEFAULT_CALIBRATION_MS);
     const measurementMs = Math.max(100, parseInt(document.getElementById('measurement-ms').value) || DEFAULT_MEASUREMENT_MS);
-    return { calibrationMs, measurementMs };
+    const warmupMs = parseInt(document.getElementById('warmup-ms').value);
+    const precisionPercent = parseFloat(document.getElementById('target-precision').value);
+    const maxTimeMs = parseInt(document.getElementById('max-time-ms').value);
+    const timeoutMs = parseInt(document.getElementById('timeout-ms').value);
+    const pinCore = parseInt(document.getElementById('pin-core').value);
+    // Field names match `RunnerOptions` in vello_bench_core.
+    const options = {
+        warmup_ms: warmupMs >= 0 ? warmupMs : DEFAULT_WARMUP_MS,
+        exclude_outliers: document.getElementById('exclude-outliers').checked,
+        perf_counters: document.getElementById('perf-counters').checked,
+        pin_core: pinCore >= 0 ? pinCore : null,
+        raise_priority: document.getElementById('raise-priority').checked,
+        strict: document.getElementById('strict-preflight').checked,
+        confidence_level: parseFloat(document.getElementById('confidence-level').value) || DEFAULT_CONFIDENCE_LEVEL,
+        target_precision: precisionPercent > 0 ? precisionPercent / 100 : null,
+        max_time_ms: maxTimeMs > 0 ? maxTimeMs : null,
+        timeout_ms: timeoutMs > 0 ? timeoutMs : null,
+        timer: document.getElementById('timer').value || null,
+    };
+    return { calibrationMs, measurementMs, options };
+}
+
+function formatCounters(counters) {
+    if (!counters) return '';
+    const lines = [
+        ['instructions', counters.instructions],
+        ['cycles', counters.cycles],
+        ['branch misses', counters.branch_misses],
+        ['cache misses', counters.cache_misses],
+        ['context switches', counters.context_switches],
+    ].filter(([, value]) => value != null).map(([name, value]) => `${name}: ${value.toFixed(1)}/iter`);
+    if (counters.instructions != null && counters.cycles) {
+        lines.push(`IPC: ${(counters.instructions / counters.cycles).toFixed(2)}`);
+    }
+    if (counters.task_clock_ns != null) {
+        lines.push(`task clock: ${formatNs(counters.task_clock_ns)}/iter`);
+    }
+    if (lines.length === 0) return 'perf counters: none permitted';
+    return lines.join('\n') + (counters.multiplexed ? '\n(multiplexed, values extrapolated)' : '');
+}
+
+function formatDiagnostics(diagnostics) {
+    const batches = diagnostics?.calibration_batches;
+    if (!batches || batches.length === 0) return '';
+    const first = batches[0];
+    const last = batches[batches.length - 1];
+    return [
+        `warm-up: ${diagnostics.warmup_iterations} iterations in ${formatNs(diagnostics.warmup_ns)}`,
+        `calibration: ${batches.length} batches, first ${formatNs(first.elapsed_ns / first.iterations)}/iter, `
+            + `last ${formatNs(last.elapsed_ns / last.iterations)}/iter`,
+    ].join('\n');
+}
+
+// Parses the params field ("width=64 alpha=0.5") into overrides for the benchmark `id`,
+// keeping only the parameters it declares, so one field can serve a mixed selection.
+// Throws if a numeric parameter the benchmark declares is given something else.
+function getParamOverrides(id) {
+    const declared = state.benchmarks.find(b => b.id === id)?.params ?? [];
+    const params = {};
+    for (const term of document.getElementById('params').value.split(/\s+/)) {
+        const [name, value] = term.split('=');
+        const param = declared.find(p => p.name === name);
+        if (!param || value === undefined) continue;
+        if (param.type === 'choice') {
+            params[name] = value;
+        } else if (value.trim() !== '' && Number.isFinite(Number(value))) {
+            params[name] = Number(value);
+        } else {
+            throw new Error(`${name} must be a number, got "${value}"`);
+        }
+    }
+    return params;
+}
+
+function checkParamOverrides(ids) {
+    try {
+        ids.forEach(getParamOverrides);
+        return true;
+    } catch (e) {
+        alert(`Not running: ${e.message}`);
+        return false;
+    }
+}
+
+function formatParams(declared, values) {
+    if (!declared || declared.length === 0) return '';
+    return declared.map(p => {
+        const allowed = p.type === 'choice' ? p.options.join('|') : `${p.min}..${p.max}`;
+        const value = values?.[p.name] ?? p.default;
+        return `${p.name} = ${value} (${allowed}): ${p.description}`;
+    }).join('\n');
 }
 
 async function runSingleBenchmark(id) {
     const simdLevel = document.getElementById('simd-level').value;
-    const { calibrationMs, measurementMs } = getTimingConfig();
+    const { calibrationMs, measurementMs, options } = getTimingConfig();
+    const params = getParamOverrides(id);
 
     if (state.executionMode === 'native' && state.isTauri) {
-        return await invoke('run_benchmark', { id, simdLevel, calibrationMs, measurementMs });
+        return await invoke('run_benchmark', { id, simdLevel, calibrationMs, measurementMs, options, params });
     } else if (state.wasmWorker) {
-        return new Promise((resolve) => {
+        const data = await new Promise((resolve) => {
             state.pendingWasmResolve = resolve;
-            state.wasmWorker.postMessage({ type: 'run', id, calibrationMs, measurementMs });
+            state.wasmWorker.postMessage({ type: 'run', id, simdLevel, calibrationMs, measurementMs, options, params });
         });
+        if (data.error) throw new Error(data.error);
+        return data.result;
     }
     return null;
 }
@@ -410,14 +732,42 @@ function abortBenchmarks() {
     }
 }
 
+async function runPreflightChecks() {
+    const banner = document.getElementById('preflight-warnings');
+    banner.style.display = 'none';
+    if (state.executionMode !== 'native' || !state.isTauri) return true;
+
+    const strict = document.getElementById('strict-preflight').checked;
+    try {
+        const report = await invoke('preflight_checks', { strict });
+        if (report.warnings.length > 0) {
+            banner.textContent = `Noisy system: ${report.warnings.map(w => w.message).join('; ')}`;
+            banner.style.display = 'block';
+        }
+        return true;
+    } catch (e) {
+        alert(`Not running: ${e}`);
+        return false;
+    }
+}
+
 async function runBenchmarks(ids) {
     if (state.isRunning || ids.length === 0) return;
+    if (!checkParamOverrides(ids) || !await runPreflightChecks()) return;
 
     state.isRunning = true;
     state.abortRequested = false;
 
+    try {
+        state.environment = await getEnvironment();
+    } catch (e) {
+        console.error('Failed to get environment:', e);
+        state.environment = null;
+    }
+
     for (const id of ids) {
         state.results.delete(id);
+        state.errors.delete(id);
         state.queuedBenchmarks.add(id);
     }
     renderBenchmarks();
@@ -437,7 +787,7 @@ async function runBenchmarks(ids) {
                 state.runningPhase = 'measuring';
                 renderBenchmarks();
             }
-        }, getTimingConfig().calibrationMs);
+        }, getTimingConfig().options.warmup_ms + getTimingConfig().calibrationMs);
 
         await new Promise(resolve => setTimeout(resolve, 0));
 
@@ -445,8 +795,11 @@ async function runBenchmarks(ids) {
             const result = await runSingleBenchmark(id);
             if (result) {
                 state.results.set(id, result);
+                await refreshComparisons();
             }
         } catch (e) {
+            // Tauri rejects with the error message itself.
+            state.errors.set(id, e.message ?? String(e));
             console.error(`Failed to run benchmark ${id}:`, e);
         }
 
@@ -467,20 +820,288 @@ async function runBenchmarks(ids) {
 function updateRunButtons() {
     const runBtn = document.getElementById('run-btn');
     const abortBtn = document.getElementById('abort-btn');
+    const compareBtn = document.getElementById('compare-btn');
+    const validateBtn = document.getElementById('validate-btn');
+    const matrixBtn = document.getElementById('matrix-btn');
+    const canCompare = state.executionMode === 'native' && state.isTauri;
 
     if (state.isRunning) {
         runBtn.style.display = 'none';
         if (abortBtn) abortBtn.style.display = 'inline-block';
+        compareBtn.style.display = 'none';
+        validateBtn.style.display = 'none';
+        matrixBtn.style.display = 'none';
     } else {
         runBtn.style.display = 'inline-block';
         runBtn.disabled = state.benchmarks.length === 0;
         if (abortBtn) abortBtn.style.display = 'none';
+        compareBtn.style.display = canCompare ? 'inline-block' : 'none';
+        compareBtn.disabled = state.benchmarks.length === 0;
+        validateBtn.style.display = 'inline-block';
+        validateBtn.disabled = state.benchmarks.length === 0;
+        matrixBtn.style.display = 'inline-block';
+        matrixBtn.disabled = state.benchmarks.length === 0;
     }
 }
 
+// Runs every SIMD level of each benchmark with interleaved samples (native only), so the
+// levels are compared under the same thermal and frequency conditions.
+async function compareLevels(ids) {
+    if (state.isRunning || ids.length === 0) return;
+    if (!checkParamOverrides(ids) || !await runPreflightChecks()) return;
+
+    const levels = Array.from(document.getElementById('simd-level').options).map(o => o.value);
+    const { calibrationMs, measurementMs, options } = getTimingConfig();
+    const panel = document.getElementById('comparison-panel');
+    const tbody = document.getElementById('comparison-tbody');
+    tbody.innerHTML = '';
+    panel.style.display = 'block';
+
+    state.isRunning = true;
+    state.abortRequested = false;
+    updateRunButtons();
+
+    for (const id of ids) {
+        if (state.abortRequested) break;
+
+        state.runningBenchmark = id;
+        state.runningPhase = 'measuring';
+        renderBenchmarks();
+
+        if (state.benchmarks.find(b => b.id === id)?.level_agnostic) {
+            tbody.insertAdjacentHTML('beforeend',
+                `<tr><td>${id}${LEVEL_AGNOSTIC_LABEL}</td><td colspan="3">Skipped: every level runs the same code</td></tr>`);
+            continue;
+        }
+
+        try {
+            const params = getParamOverrides(id);
+            const variants = levels.map(simd_level => ({ id, simd_level, params }));
+            const result = await invoke('run_interleaved', { variants, calibrationMs, measurementMs, options });
+            tbody.insertAdjacentHTML('beforeend', renderComparisonRows(id, result));
+        } catch (e) {
+            tbody.insertAdjacentHTML('beforeend',
+                `<tr><td>${id}</td><td colspan="3">Failed: ${escapeHtml(e.message ?? String(e))}</td></tr>`);
+            console.error(`Failed to compare levels of ${id}:`, e);
+        }
+    }
+
+    state.runningBenchmark = null;
+    state.runningPhase = null;
+    state.isRunning = false;
+    state.abortRequested = false;
+    renderBenchmarks();
+    updateRunButtons();
+}
+
+function renderComparisonRows(id, result) {
+    return result.results.map((r, i) => {
+        const diff = result.differences.find(d => d.variant === i);
+        let change = 'baseline';
+        if (diff) {
+            const signed = ns => `${ns >= 0 ? '+' : '-'}${formatNs(Math.abs(ns))}`;
+            const ci = diff.mean_diff_ci;
+            change = `${signed(diff.mean_diff_ns)} (${(diff.relative_diff * 100).toFixed(1)}%) `
+                + `[${signed(ci.lower_ns)}, ${signed(ci.upper_ns)}]`;
+        }
+        return `<tr>
+            <td class="col-name">${i === 0 ? id : ''}</td>
+            <td class="col-status">${r.simd_variant}</td>
+            <td class="col-mean">${formatNs(r.statistics.mean_ns)}</td>
+            <td class="col-change">${change}</td>
+        </tr>`;
+    }).join('');
+}
+
+// Runs each benchmark at every SIMD level, one benchmark at a time so progress shows, and
+// tabulates the speedups over scalar and the best level.
+async function runLevelMatrix(ids) {
+    if (state.isRunning || ids.length === 0) return;
+    if (!checkParamOverrides(ids) || !await runPreflightChecks()) return;
+
+    state.levelMatrix = null;
+    document.getElementById('matrix-panel').style.display = 'block';
+    renderLevelMatrix();
+
+    state.isRunning = true;
+    state.abortRequested = false;
+    updateRunButtons();
+
+    for (const id of ids) {
+        if (state.abortRequested) break;
+
+        state.runningBenchmark = id;
+        state.runningPhase = 'measuring';
+        renderBenchmarks();
+
+        try {
+            const matrix = await runLevelMatrixRow(id);
+            if (!matrix) throw new Error('No result');
+            if (state.levelMatrix) {
+                state.levelMatrix.rows.push(...matrix.rows);
+            } else {
+                state.levelMatrix = matrix;
+            }
+            renderLevelMatrix();
+        } catch (e) {
+            console.error(`Failed to run ${id} at all levels:`, e);
+        }
+    }
+
+    state.runningBenchmark = null;
+    state.runningPhase = null;
+    state.isRunning = false;
+    state.abortRequested = false;
+    renderBenchmarks();
+    updateRunButtons();
+}
+
+async function runLevelMatrixRow(id) {
+    const { calibrationMs, measurementMs, options } = getTimingConfig();
+    const params = getParamOverrides(id);
+    // Same layout as `Selection` in vello_bench_core; ids contain no glob characters.
+    const selection = { include: [id] };
+
+    if (state.executionMode === 'native' && state.isTauri) {
+        return await invoke('run_level_matrix', { selection, calibrationMs, measurementMs, options, params });
+    } else if (state.wasmWorker) {
+        return new Promise((resolve) => {
+            state.pendingWasmResolve = resolve;
+            state.wasmWorker.postMessage({ type: 'matrix', selection, calibrationMs, measurementMs, options, params });
+        });
+    }
+    return null;
+}
+
+function renderLevelMatrix() {
+    const matrix = state.levelMatrix;
+    const thead = document.getElementById('matrix-thead');
+    const tbody = document.getElementById('matrix-tbody');
+    if (!matrix) {
+        thead.innerHTML = '';
+        tbody.innerHTML = '';
+        return;
+    }
+
+    thead.innerHTML = `<tr><th class="col-name">Benchmark</th>${
+        matrix.levels.map(level => `<th class="col-mean" title="Mean, and speedup over scalar">${level}</th>`).join('')
+    }</tr>`;
+    tbody.innerHTML = matrix.rows.map(row => `<tr>
+            <td class="col-name">${row.id}${row.level_agnostic ? LEVEL_AGNOSTIC_LABEL : ''}</td>
+            ${row.cells.map(cell => {
+                if (!cell.result && !cell.error) {
+                    return '<td class="col-mean" title="Not run: same code as scalar">-</td>';
+                }
+                if (!cell.result) {
+                    return `<td class="col-mean" title="${escapeHtml(cell.error ?? '')}">failed</td>`;
+                }
+                const best = cell.simd_level === row.best_level;
+                const vsScalar = cell.speedup_vs_scalar != null ? ` (${cell.speedup_vs_scalar.toFixed(2)}x)` : '';
+                const vsBest = cell.speedup_vs_best != null ? `${cell.speedup_vs_best.toFixed(2)}x of the best level` : '';
+                return `<td class="col-mean ${best ? 'matrix-best' : ''}" title="${vsBest}">${formatNs(cell.result.statistics.mean_ns)}${vsScalar}</td>`;
+            }).join('')}
+        </tr>`).join('');
+}
+
+function exportLevelMatrix() {
+    const matrix = state.levelMatrix;
+    if (!matrix) return;
+
+    const header = ['benchmark', 'level_agnostic', ...matrix.levels.flatMap(l => [`${l}_mean_ns`, `${l}_vs_scalar`, `${l}_vs_best`])];
+    const rows = matrix.rows.map(row => [
+        row.id,
+        row.level_agnostic ?? false,
+        ...row.cells.flatMap(c => [c.result?.statistics.mean_ns ?? '', c.speedup_vs_scalar ?? '', c.speedup_vs_best ?? '']),
+    ]);
+    const csv = [header, ...rows].map(fields => fields.join(',')).join('\n') + '\n';
+    const blob = new Blob([csv], { type: 'text/csv' });
+    const url = URL.createObjectURL(blob);
+
+    const a = document.createElement('a');
+    a.href = url;
+    a.download = `vello-bench-levels-${Date.now()}.csv`;
+    a.click();
+
+    URL.revokeObjectURL(url);
+}
+
+// Runs each benchmark once per SIMD level and checks that the outputs match scalar, so a
+// SIMD path that is fast because it's wrong doesn't pass as a speedup.
+async function validateLevels(ids) {
+    if (state.isRunning || ids.length === 0) return;
+    if (!checkParamOverrides(ids)) return;
+
+    const panel = document.getElementById('validation-panel');
+    const tbody = document.getElementById('validation-tbody');
+    tbody.innerHTML = '';
+    panel.style.display = 'block';
+
+    state.isRunning = true;
+    state.abortRequested = false;
+    updateRunButtons();
+
+    for (const id of ids) {
+        if (state.abortRequested) break;
+
+        state.runningBenchmark = id;
+        state.runningPhase = 'validating';
+        renderBenchmarks();
+
+        try {
+            const validation = await validateBenchmark(id, getParamOverrides(id));
+            tbody.insertAdjacentHTML('beforeend', renderValidationRows(validation));
+        } catch (e) {
+            tbody.insertAdjacentHTML('beforeend',
+                `<tr class="validation-failed"><td>${id}</td><td></td><td class="col-validation">Failed: ${escapeHtml(e.message ?? String(e))}</td></tr>`);
+            console.error(`Failed to validate ${id}:`, e);
+        }
+    }
+
+    state.runningBenchmark = null;
+    state.runningPhase = null;
+    state.isRunning = false;
+    state.abortRequested = false;
+    renderBenchmarks();
+    updateRunButtons();
+}
+
+async function validateBenchmark(id, params) {
+    if (state.executionMode === 'native' && state.isTauri) {
+        return await invoke('validate_benchmark', { id, params });
+    } else if (state.wasmWorker) {
+        const data = await new Promise((resolve) => {
+            state.pendingWasmResolve = resolve;
+            state.wasmWorker.postMessage({ type: 'validate', id, params });
+        });
+        if (data.error) throw new Error(data.error);
+        return data.validation;
+    }
+    throw new Error('No backend available');
+}
+
+function renderValidationRows(validation) {
+    const row = (level, text, failed) => `<tr class="${failed ? 'validation-failed' : ''}">
+            <td class="col-name">${validation.id}</td>
+            <td class="col-status">${level}</td>
+            <td class="col-validation">${escapeHtml(text)}</td>
+        </tr>`;
+
+    if (validation.level_agnostic) return row('-', 'level-agnostic, only checked at scalar', false);
+    if (validation.outputs.length === 0) return row('-', 'no outputs recorded', false);
+    if (validation.levels.length === 0) return row('-', `only ${validation.reference_level} is available`, false);
+    return validation.levels.map(level => {
+        const m = level.mismatch;
+        const text = m
+            ? `${m.count} differ(s) in ${m.output}, first at [${m.index}]: expected ${m.expected ?? 'nothing'}, got ${m.actual ?? 'nothing'}`
+            : `${validation.outputs.join(', ')} match`;
+        return row(level.simd_level, text, m != null);
+    }).join('');
+}
+
 function exportResults() {
     const results = Array.from(state.results.values());
-    const json = JSON.stringify(results, null, 2);
+    // Same layout as `ResultSet` in vello_bench_core.
+    const json = JSON.stringify({ environment: state.environment, results }, null, 2);
     const blob = new Blob([json], { type: 'application/json' });
     const url = URL.createObjectURL(blob);
 
@@ -570,7 +1191,7 @@ async function saveReference() {
 
     try {
         const results = Array.from(state.results.values());
-        await invoke('save_reference', { name, results });
+        await invoke('save_reference', { name, results, environment: state.environment });
         await loadReferencesList();
     } catch (e) {
         console.error('Failed to save reference:', e);
@@ -581,20 +1202,22 @@ async function saveReference() {
 async function loadReference(name) {
     if (!name) {
         state.loadedReference = null;
+        state.referenceEnvironment = null;
         state.referenceResults.clear();
-        renderBenchmarks();
+        await refreshComparisons();
         updateReferenceUI();
         return;
     }
 
     try {
-        const results = await invoke('load_reference', { name });
+        const resultSet = await invoke('load_reference', { name });
         state.loadedReference = name;
+        state.referenceEnvironment = resultSet.environment ?? null;
         state.referenceResults.clear();
-        for (const result of results) {
+        for (const result of resultSet.results) {
             state.referenceResults.set(result.id, result);
         }
-        renderBenchmarks();
+        await refreshComparisons();
         updateReferenceUI();
     } catch (e) {
         console.error('Failed to load reference:', e);
@@ -641,9 +1264,10 @@ async function deleteReference() {
 
         if (state.loadedReference === name) {
             state.loadedReference = null;
+            state.referenceEnvironment = null;
             state.referenceResults.clear();
             select.value = '';
-            renderBenchmarks();
+            await refreshComparisons();
             updateReferenceUI();
         }
 
@@ -674,45 +1298,64 @@ function updateReferenceUI() {
         } else {
             currentName.textContent = 'None';
         }
+        currentName.title = formatEnvironment(state.referenceEnvironment);
     }
 }
 
-function calculateComparison(currentNs, referenceNs) {
-    if (!referenceNs || referenceNs === 0) return null;
+const COMPARE_METHODS = {
+    mann_whitney_u: 'Mann-Whitney U test',
+    interval_overlap: 'confidence interval overlap',
+    threshold: 'fixed threshold (no samples or intervals)',
+};
 
-    const diff = currentNs - referenceNs;
-    let percentChange = (diff / referenceNs) * 100;
-    const speedup = referenceNs / currentNs;
+function formatComparison(comparison) {
+    return [
+        `${formatNs(comparison.baseline_mean_ns)} \u2192 ${formatNs(comparison.current_mean_ns)}`,
+        comparison.p_value != null ? `p = ${comparison.p_value.toPrecision(3)}` : '',
+        `by ${COMPARE_METHODS[comparison.method] ?? comparison.method}`,
+    ].filter(Boolean).join('\n');
+}
 
-    // Round small values to just 0.
-    if (Math.abs(percentChange) < 0.005) {
-        percentChange = 0;
+async function compareResults(baseline, current) {
+    if (state.isTauri) {
+        return await invoke('compare_results', { baseline, current, options: null });
+    } else if (state.wasmWorker && !state.pendingWasmResolve) {
+        // A pending request means the worker is busy running a benchmark; the run loop
+        // refreshes the comparisons once it's done.
+        return new Promise((resolve) => {
+            state.pendingWasmResolve = resolve;
+            state.wasmWorker.postMessage({ type: 'compare', baseline, current });
+        });
     }
+    return null;
+}
 
-    let status;
-    if (Math.abs(percentChange) <= 5) {
-        status = 'similar';
-    } else if (percentChange < 0) {
-        status = 'faster';
-    } else {
-        status = 'slower';
+async function refreshComparisons() {
+    const baseline = Array.from(state.referenceResults.values());
+    const current = Array.from(state.results.values());
+    let set = null;
+    if (baseline.length > 0 && current.length > 0) {
+        try {
+            set = await compareResults(baseline, current);
+        } catch (e) {
+            console.error('Failed to compare results:', e);
+        }
     }
-
-    return { diff, percentChange, speedup, status };
+    state.comparisons = new Map((set?.comparisons ?? []).map(c => [c.id, c]));
+    state.unmatched = new Map((set?.unmatched ?? []).map(u => [u.id, u.reason]));
+    renderBenchmarks();
 }
 
 function setupEventListeners() {
     document.getElementById('exec-mode').addEventListener('change', async (e) => {
         state.executionMode = e.target.value;
         await loadSimdLevels();
+        await loadTimers();
         await loadBenchmarks();
+        await applySelection();
     });
 
-    document.getElementById('simd-level').addEventListener('change', async (e) => {
-        if (state.executionMode === 'wasm') {
-            await switchWasmSimdLevel(e.target.value);
-        }
-    });
+    document.getElementById('selection').addEventListener('change', applySelection);
 
     document.getElementById('category-list').addEventListener('click', (e) => {
         const toggle = e.target.closest('.tree-toggle');
@@ -774,6 +1417,28 @@ function setupEventListeners() {
         runBenchmarks(ids);
     });
 
+    document.getElementById('compare-btn').addEventListener('click', () => {
+        const visible = getFilteredBenchmarks();
+        const selectedSet = new Set(state.selectedBenchmarks);
+        const ids = visible.filter(b => selectedSet.size === 0 || selectedSet.has(b.id)).map(b => b.id);
+        compareLevels(ids);
+    });
+
+    document.getElementById('matrix-btn').addEventListener('click', () => {
+        const visible = getFilteredBenchmarks();
+        const selectedSet = new Set(state.selectedBenchmarks);
+        const ids = visible.filter(b => selectedSet.size === 0 || selectedSet.has(b.id)).map(b => b.id);
+        runLevelMatrix(ids);
+    });
+    document.getElementById('matrix-export').addEventListener('click', exportLevelMatrix);
+
+    document.getElementById('validate-btn').addEventListener('click', () => {
+        const visible = getFilteredBenchmarks();
+        const selectedSet = new Set(state.selectedBenchmarks);
+        const ids = visible.filter(b => selectedSet.size === 0 || selectedSet.has(b.id)).map(b => b.id);
+        validateLevels(ids);
+    });
+
     document.getElementById('abort-btn').addEventListener('click', abortBenchmarks);
     document.getElementById('export-results').addEventListener('click', exportResults);
 
@@ -25,15 +25,100 @@ self.onmessage = async function(e) {
 
         case 'run':
             if (!wasmModule) {
-                self.postMessage({ type: 'error', id: data.id, error: 'WASM not loaded' });
+                self.postMessage({ type: 'result', id: data.id, result: null, error: 'WASM not loaded' });
                 return;
             }
 
             try {
-                const result = wasmModule.run_benchmark(data.id, data.calibrationMs, data.measurementMs);
+                const result = wasmModule.run_benchmark(
+                    data.id, data.simdLevel, data.calibrationMs, data.measurementMs, data.options, data.params,
+                );
                 self.postMessage({ type: 'result', id: data.id, result });
             } catch (e) {
-                self.postMessage({ type: 'error', id: data.id, error: e.message });
+                // Errors from the bindings are plain strings; panics are `Error`s.
+                self.postMessage({ type: 'result', id: data.id, result: null, error: e.message ?? String(e) });
+            }
+            break;
+
+        case 'matrix':
+            if (!wasmModule) {
+                self.postMessage({ type: 'error', error: 'WASM not loaded' });
+                return;
+            }
+            try {
+                const matrix = wasmModule.run_level_matrix(
+                    data.selection, data.calibrationMs, data.measurementMs, data.options, data.params,
+                );
+                self.postMessage({ type: 'matrix', matrix });
+            } catch (e) {
+                self.postMessage({ type: 'error', error: e.message ?? String(e) });
+            }
+            break;
+
+        case 'validate':
+            if (!wasmModule) {
+                self.postMessage({ type: 'validation', validation: null, error: 'WASM not loaded' });
+                return;
+            }
+            try {
+                const validation = wasmModule.validate_benchmark(data.id, data.params);
+                self.postMessage({ type: 'validation', validation });
+            } catch (e) {
+                self.postMessage({ type: 'validation', validation: null, error: e.message ?? String(e) });
+            }
+            break;
+
+        case 'levels':
+            if (!wasmModule) {
+                self.postMessage({ type: 'levels', levels: [] });
+                return;
+            }
+            try {
+                self.postMessage({ type: 'levels', levels: wasmModule.get_simd_levels() });
+            } catch (e) {
+                self.postMessage({ type: 'error', error: e.message });
+            }
+            break;
+
+        case 'environment':
+            if (!wasmModule) {
+                self.postMessage({ type: 'environment', environment: null });
+                return;
+            }
+            try {
+                const environment = wasmModule.get_environment(
+                    data.calibrationMs, data.measurementMs, data.options,
+                );
+                self.postMessage({ type: 'environment', environment });
+            } catch (e) {
+                self.postMessage({ type: 'error', error: e.message ?? String(e) });
+            }
+            break;
+
+        case 'compare':
+            if (!wasmModule) {
+                self.postMessage({ type: 'comparisons', comparisons: { comparisons: [], unmatched: [] } });
+                return;
+            }
+            try {
+                const comparisons = wasmModule.compare_results(data.baseline, data.current, data.options);
+                self.postMessage({ type: 'comparisons', comparisons });
+            } catch (e) {
+                self.postMessage({ type: 'error', error: e.message ?? String(e) });
+            }
+            break;
+
+        case 'select':
+            if (!wasmModule) {
+                self.postMessage({ type: 'selection', benchmarks: [] });
+                return;
+            }
+            try {
+                const benchmarks = wasmModule.select_benchmarks(data.selection);
+                self.postMessage({ type: 'selection', benchmarks });
+            } catch (e) {
+                // Errors from the bindings are plain strings.
+                self.postMessage({ type: 'selection', benchmarks: null, error: e.message ?? String(e) });
             }
             break;
 
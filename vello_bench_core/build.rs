@@ -0,0 +1,55 @@
+//! Record build information for `RunEnvironment`.
+
+use std::path::Path;
+use std::process::Command;
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+
+    let rustc = std::env::var("RUSTC").unwrap_or_else(|_| "rustc".to_string());
+    if let Some(version) = command_output(Command::new(rustc).arg("--version")) {
+        println!("cargo:rustc-env=VELLO_BENCH_RUSTC_VERSION={version}");
+    }
+
+    let profile = std::env::var("PROFILE").unwrap_or_default();
+    println!("cargo:rustc-env=VELLO_BENCH_PROFILE={profile}");
+
+    let target_features = std::env::var("CARGO_CFG_TARGET_FEATURE").unwrap_or_default();
+    println!("cargo:rustc-env=VELLO_BENCH_TARGET_FEATURES={target_features}");
+
+    // Prefer an explicit revision (e.g. from CI), else ask git in the vello checkout that
+    // the workspace's path dependencies point to.
+    println!("cargo:rerun-if-env-changed=VELLO_REVISION");
+    let revision = std::env::var("VELLO_REVISION").ok().or_else(|| {
+        let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").ok()?;
+        let vello = Path::new(&manifest_dir).join("../../vello");
+        // HEAD only changes when switching branches, so also watch the branch it points to,
+        // and the sources themselves for the dirty flag.
+        let branch = std::fs::read_to_string(vello.join(".git/HEAD"))
+            .ok()
+            .and_then(|head| head.trim().strip_prefix("ref: ").map(|r| format!(".git/{r}")));
+        let watched = [".git/HEAD", ".git/index", "sparse_strips/vello_common", "sparse_strips/vello_cpu"];
+        for file in watched.into_iter().map(str::to_string).chain(branch) {
+            if vello.join(&file).exists() {
+                println!("cargo:rerun-if-changed={}", vello.join(&file).display());
+            }
+        }
+
+        let revision = command_output(Command::new("git").arg("-C").arg(&vello).args(["rev-parse", "--short=12", "HEAD"]))?;
+        let dirty = command_output(Command::new("git").arg("-C").arg(&vello).args(["status", "--porcelain", "--untracked-files=no"]))
+            .is_some_and(|status| !status.is_empty());
+        Some(if dirty { format!("{revision}-dirty") } else { revision })
+    });
+    if let Some(revision) = revision {
+        println!("cargo:rustc-env=VELLO_BENCH_VELLO_REVISION={revision}");
+    }
+}
+
+/// Run `command` and return its trimmed stdout, if it succeeded.
+fn command_output(command: &mut Command) -> Option<String> {
+    let output = command.output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
@@ -0,0 +1,129 @@
+//! Pinning the benchmark thread to a core and raising its scheduling priority.
+//!
+//! Both are only implemented on Linux, and both are best effort: if the core does not exist
+//! or the process may not raise its priority, the thread is left as it was. Elsewhere,
+//! nothing is changed.
+
+/// Placement applied to the calling thread. Dropping it restores the previous CPU mask and
+/// priority, so threads from a shared pool don't stay pinned.
+pub struct ThreadPlacement {
+    pinned_core: Option<usize>,
+    nice: Option<i32>,
+    #[cfg(target_os = "linux")]
+    previous_mask: Option<libc::cpu_set_t>,
+    #[cfg(target_os = "linux")]
+    previous_nice: Option<i32>,
+}
+
+impl ThreadPlacement {
+    /// Pin the calling thread to `core`, if given, and give it the highest priority the
+    /// process is allowed to set, if `raise_priority` is set.
+    pub fn apply(core: Option<usize>, raise_priority: bool) -> Self {
+        #[cfg(target_os = "linux")]
+        {
+            let mut placement = Self { pinned_core: None, nice: None, previous_mask: None, previous_nice: None };
+            if let Some(core) = core {
+                placement.previous_mask = linux::affinity();
+                let mask = placement.previous_mask.and_then(|_| linux::single_core_mask(core));
+                if mask.is_some_and(|mask| linux::set_affinity(&mask)) {
+                    placement.pinned_core = Some(core);
+                }
+            }
+            if raise_priority {
+                placement.previous_nice = linux::nice();
+                placement.nice = placement.previous_nice.and_then(linux::raise_nice);
+            }
+            placement
+        }
+        #[cfg(not(target_os = "linux"))]
+        {
+            let _ = (core, raise_priority);
+            Self { pinned_core: None, nice: None }
+        }
+    }
+
+    /// The core the thread was pinned to, if pinning succeeded.
+    pub fn pinned_core(&self) -> Option<usize> {
+        self.pinned_core
+    }
+
+    /// The raised nice value of the thread, if raising the priority succeeded.
+    pub fn nice(&self) -> Option<i32> {
+        self.nice
+    }
+}
+
+impl Drop for ThreadPlacement {
+    fn drop(&mut self) {
+        #[cfg(target_os = "linux")]
+        {
+            if let (Some(_), Some(mask)) = (self.pinned_core, &self.previous_mask) {
+                linux::set_affinity(mask);
+            }
+            if let (Some(_), Some(previous)) = (self.nice, self.previous_nice) {
+                linux::set_nice(previous);
+            }
+        }
+    }
+}
+
+impl std::fmt::Debug for ThreadPlacement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ThreadPlacement")
+            .field("pinned_core", &self.pinned_core)
+            .field("nice", &self.nice)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(target_os = "linux")]
+#[allow(unsafe_code)]
+mod linux {
+    /// Id of the calling thread, which Linux accepts wherever it accepts a process id.
+    fn thread_id() -> libc::id_t {
+        // SAFETY: `gettid` has no arguments and cannot fail.
+        unsafe { libc::syscall(libc::SYS_gettid) as libc::id_t }
+    }
+
+    /// A mask holding only `core`, or `None` if the core doesn't fit in a `cpu_set_t`.
+    pub(super) fn single_core_mask(core: usize) -> Option<libc::cpu_set_t> {
+        if core >= 8 * size_of::<libc::cpu_set_t>() {
+            return None;
+        }
+        // SAFETY: `cpu_set_t` is a plain bit set, for which all zeroes is valid.
+        let mut mask: libc::cpu_set_t = unsafe { std::mem::zeroed() };
+        // SAFETY: `CPU_SET` doesn't check bounds, but `core` was checked to be within the set.
+        unsafe { libc::CPU_SET(core, &mut mask) };
+        Some(mask)
+    }
+
+    pub(super) fn affinity() -> Option<libc::cpu_set_t> {
+        // SAFETY: As above.
+        let mut mask: libc::cpu_set_t = unsafe { std::mem::zeroed() };
+        // SAFETY: `mask` is valid for writes of the given size, and 0 means the calling thread.
+        let ret = unsafe { libc::sched_getaffinity(0, size_of::<libc::cpu_set_t>(), &mut mask) };
+        (ret == 0).then_some(mask)
+    }
+
+    pub(super) fn set_affinity(mask: &libc::cpu_set_t) -> bool {
+        // SAFETY: `mask` is valid for reads of the given size, and 0 means the calling thread.
+        unsafe { libc::sched_setaffinity(0, size_of::<libc::cpu_set_t>(), mask) == 0 }
+    }
+
+    pub(super) fn nice() -> Option<i32> {
+        // The raw syscall returns `20 - nice`, which avoids the ambiguous -1 of `getpriority`.
+        // SAFETY: Plain integer arguments.
+        let ret = unsafe { libc::syscall(libc::SYS_getpriority, libc::PRIO_PROCESS, thread_id()) };
+        (ret > 0).then(|| 20 - ret as i32)
+    }
+
+    pub(super) fn set_nice(nice: i32) -> bool {
+        // SAFETY: Plain integer arguments; on Linux this affects only the given thread.
+        unsafe { libc::setpriority(libc::PRIO_PROCESS, thread_id(), nice) == 0 }
+    }
+
+    /// Lower the nice value as far as allowed (down to -20), returning the new value.
+    pub(super) fn raise_nice(current: i32) -> Option<i32> {
+        (-20..current).find(|&nice| set_nice(nice))
+    }
+}
@@ -0,0 +1,109 @@
+//! Allocation counting for benchmark iterations.
+//!
+//! With the `alloc-tracking` feature on a native target, this installs a counting global
+//! allocator. Counts are kept per thread and only while tracking is switched on, which the
+//! runner does around the timed part of each measurement batch. Without the feature, all
+//! functions are no-ops and no counts are reported.
+
+/// Raw allocator event counts. Reallocations count as one allocation and one deallocation.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AllocationCounts {
+    pub allocations: u64,
+    pub deallocations: u64,
+    pub bytes_allocated: u64,
+}
+
+/// Whether allocation tracking is compiled in.
+pub fn is_enabled() -> bool {
+    cfg!(all(feature = "alloc-tracking", not(target_arch = "wasm32")))
+}
+
+/// Start or stop counting allocations on the current thread.
+pub fn set_tracking(enabled: bool) {
+    #[cfg(all(feature = "alloc-tracking", not(target_arch = "wasm32")))]
+    counting::TRACKING.with(|tracking| tracking.set(enabled));
+    #[cfg(not(all(feature = "alloc-tracking", not(target_arch = "wasm32"))))]
+    let _ = enabled;
+}
+
+/// Reset the counts of the current thread.
+pub fn reset() {
+    #[cfg(all(feature = "alloc-tracking", not(target_arch = "wasm32")))]
+    counting::COUNTS.with(|counts| counts.set(AllocationCounts::default()));
+}
+
+/// Counts of the current thread since the last `reset`, or `None` if tracking is not
+/// compiled in.
+pub fn counts() -> Option<AllocationCounts> {
+    #[cfg(all(feature = "alloc-tracking", not(target_arch = "wasm32")))]
+    {
+        Some(counting::COUNTS.with(|counts| counts.get()))
+    }
+    #[cfg(not(all(feature = "alloc-tracking", not(target_arch = "wasm32"))))]
+    {
+        None
+    }
+}
+
+#[cfg(all(feature = "alloc-tracking", not(target_arch = "wasm32")))]
+mod counting {
+    use super::AllocationCounts;
+    use std::alloc::{GlobalAlloc, Layout, System};
+    use std::cell::Cell;
+
+    // Both are const-initialized and have no destructor, so accessing them never allocates.
+    thread_local! {
+        pub(super) static TRACKING: Cell<bool> = const { Cell::new(false) };
+        pub(super) static COUNTS: Cell<AllocationCounts> = const {
+            Cell::new(AllocationCounts { allocations: 0, deallocations: 0, bytes_allocated: 0 })
+        };
+    }
+
+    fn record(allocations: u64, deallocations: u64, bytes: usize) {
+        let _ = TRACKING.try_with(|tracking| {
+            if tracking.get() {
+                let _ = COUNTS.try_with(|counts| {
+                    let mut c = counts.get();
+                    c.allocations += allocations;
+                    c.deallocations += deallocations;
+                    c.bytes_allocated += bytes as u64;
+                    counts.set(c);
+                });
+            }
+        });
+    }
+
+    /// Global allocator that forwards to `System` and counts what it does.
+    struct CountingAllocator;
+
+    #[allow(unsafe_code)]
+    // SAFETY: All calls are forwarded unchanged to the system allocator.
+    unsafe impl GlobalAlloc for CountingAllocator {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            record(1, 0, layout.size());
+            // SAFETY: Forwarded with the caller's guarantees.
+            unsafe { System.alloc(layout) }
+        }
+
+        unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+            record(1, 0, layout.size());
+            // SAFETY: Forwarded with the caller's guarantees.
+            unsafe { System.alloc_zeroed(layout) }
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            record(0, 1, 0);
+            // SAFETY: Forwarded with the caller's guarantees.
+            unsafe { System.dealloc(ptr, layout) }
+        }
+
+        unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+            record(1, 1, new_size);
+            // SAFETY: Forwarded with the caller's guarantees.
+            unsafe { System.realloc(ptr, layout, new_size) }
+        }
+    }
+
+    #[global_allocator]
+    static GLOBAL: CountingAllocator = CountingAllocator;
+}
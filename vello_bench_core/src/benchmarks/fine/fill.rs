@@ -1,51 +1,102 @@
-use crate::registry::BenchmarkInfo;
-use crate::result::BenchmarkResult;
+use super::scratch_pixels;
+use crate::error::BenchError;
+use crate::params::{ParamInfo, Params};
+use crate::registry::{Benchmark, Registry};
+use crate::result::{BenchmarkResult, ThroughputUnit};
 use crate::runner::BenchRunner;
 use crate::simd::level_suffix;
 use fearless_simd::{Level, dispatch};
+use vello_common::coarse::WideTile;
 use vello_common::color::palette::css::ROYAL_BLUE;
 use vello_common::paint::{Paint, PremulColor};
 use vello_common::peniko::{BlendMode, Compose, Mix};
+use vello_common::tile::Tile;
 use vello_cpu::fine::{Fine, U8Kernel};
 
-const NAMES: &[&str] = &["opaque_short", "opaque_long", "transparent_short", "transparent_long"];
 const CATEGORY: &str = "fine/fill";
 
-pub fn list() -> Vec<BenchmarkInfo> {
-    BenchmarkInfo::from_names(CATEGORY, NAMES)
+/// A fill of one row of tiles, with the default width and alpha of its parameters. Opaque
+/// fills have no alpha parameter, so that overrides can't turn them transparent or the
+/// other way around.
+struct FillBenchmark {
+    name: &'static str,
+    width: usize,
+    alpha: Option<f64>,
 }
 
-pub fn run(name: &str, runner: &BenchRunner, level: Level) -> Option<BenchmarkResult> {
-    if !NAMES.contains(&name) {
-        return None;
-    }
-
-    let blend = BlendMode::new(Mix::Normal, Compose::SrcOver);
-
-    let (width, alpha) = match name {
-        "opaque_short" => (32, 1.0),
-        "opaque_long" => (256, 1.0),
-        "transparent_short" => (32, 0.3),
-        "transparent_long" => (256, 0.3),
-        _ => panic!("unknown fine/fill benchmark: {name}"),
-    };
-    let paint = Paint::Solid(PremulColor::from_alpha_color(ROYAL_BLUE.with_alpha(alpha)));
-
-    let simd_variant = level_suffix(level);
-
-    Some(dispatch!(level, simd => {
-        let mut fine = Fine::<_, U8Kernel>::new(simd);
-
-        runner.run(
-            &format!("{CATEGORY}/{name}"),
-            CATEGORY,
-            name,
-            simd_variant,
-            #[inline(always)]
-            || {
-                fine.fill(0, width, &paint, blend, &[], None, None);
-                std::hint::black_box(&fine);
-            },
-        )
-    }))
+/// Largest alpha of a transparent fill.
+const MAX_TRANSPARENT_ALPHA: f64 = 0.99;
+
+const BENCHMARKS: [FillBenchmark; 4] = [
+    FillBenchmark { name: "opaque_short", width: 32, alpha: None },
+    FillBenchmark { name: "opaque_long", width: 256, alpha: None },
+    FillBenchmark { name: "transparent_short", width: 32, alpha: Some(0.3) },
+    FillBenchmark { name: "transparent_long", width: 256, alpha: Some(0.3) },
+];
+
+pub fn register(registry: &mut Registry) {
+    for benchmark in BENCHMARKS {
+        registry.register(benchmark);
+    }
+}
+
+impl Benchmark for FillBenchmark {
+    /// The paint and the fill width.
+    type Input = (Paint, usize);
+
+    fn category(&self) -> &str {
+        CATEGORY
+    }
+
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn tags(&self) -> &[&str] {
+        if self.alpha.is_some() { &["transparent"] } else { &[] }
+    }
+
+    fn timed(&self) -> &str {
+        "Filling a strip of the given width with the paint in the scratch buffer."
+    }
+
+    fn params(&self) -> Vec<ParamInfo> {
+        let width = ParamInfo::int("width", "Width of the fill in pixels", self.width as i64, 1, WideTile::WIDTH.into());
+        match self.alpha {
+            Some(alpha) => vec![width, ParamInfo::float("alpha", "Opacity of the paint", alpha, 0.0, MAX_TRANSPARENT_ALPHA)],
+            None => vec![width],
+        }
+    }
+
+    fn setup(&self, params: &Params) -> Result<Self::Input, BenchError> {
+        let alpha = if self.alpha.is_some() { params.float("alpha") as f32 } else { 1.0 };
+        let paint = Paint::Solid(PremulColor::from_alpha_color(ROYAL_BLUE.with_alpha(alpha)));
+        Ok((paint, params.int("width") as usize))
+    }
+
+    fn run(&self, (paint, width): &mut Self::Input, runner: &BenchRunner, level: Level) -> BenchmarkResult {
+        let blend = BlendMode::new(Mix::Normal, Compose::SrcOver);
+        let (paint, width) = (&*paint, *width);
+
+        let simd_variant = level_suffix(level);
+
+        dispatch!(level, simd => {
+            let mut fine = Fine::<_, U8Kernel>::new(simd);
+
+            let result = runner.run(
+                &self.id(),
+                CATEGORY,
+                self.name,
+                simd_variant,
+                #[inline(always)]
+                || {
+                    fine.fill(0, width, paint, blend, &[], None, None);
+                    std::hint::black_box(&fine);
+                },
+            )
+            .with_throughput(ThroughputUnit::Pixels, (width * Tile::HEIGHT as usize) as u64);
+            runner.record_output("scratch", || scratch_pixels(&fine));
+            result
+        })
+    }
 }
@@ -1,5 +1,8 @@
-use crate::registry::BenchmarkInfo;
-use crate::result::BenchmarkResult;
+use super::{EXTENDS, extend_from_name, scratch_pixels};
+use crate::error::BenchError;
+use crate::params::{ParamInfo, Params};
+use crate::registry::{Benchmark, Registry};
+use crate::result::{BenchmarkResult, ThroughputUnit};
 use crate::runner::BenchRunner;
 use crate::simd::level_suffix;
 use fearless_simd::{Level, dispatch};
@@ -9,8 +12,9 @@ use smallvec::{SmallVec, smallvec};
 use vello_common::coarse::WideTile;
 use vello_common::color::palette::css::{BLUE, GREEN, RED, YELLOW};
 use vello_common::color::{AlphaColor, DynamicColor, Srgb};
-use vello_common::encode::EncodeExt;
+use vello_common::encode::{EncodeExt, EncodedPaint};
 use vello_common::kurbo::{Affine, Point};
+use vello_common::paint::Paint;
 use vello_common::peniko::{
     BlendMode, ColorStop, ColorStops, Compose, Gradient, GradientKind, Mix,
 };
@@ -18,31 +22,112 @@ use vello_common::tile::Tile;
 use vello_cpu::fine::{Fine, U8Kernel};
 use vello_cpu::peniko::{LinearGradientPosition, RadialGradientPosition, SweepGradientPosition};
 
-const NAMES: &[&str] = &[
-    "linear_opaque",
-    "radial_opaque",
-    "radial_opaque_conical",
-    "sweep_opaque",
-    "extend_pad",
-    "extend_repeat",
-    "extend_reflect",
-    "many_stops",
-    "transparent",
-];
 const CATEGORY: &str = "fine/gradient";
 const SEED: [u8; 32] = [0; 32];
+/// Default number of stops of `many_stops`.
+const MANY_STOPS: i64 = 121;
+
+/// A gradient fill, with the default extend mode of its parameter. The `extend_*` benchmarks
+/// have no extend parameter, so that overrides can't contradict their names.
+struct GradientBenchmark {
+    name: &'static str,
+    extend: &'static str,
+}
 
-pub fn list() -> Vec<BenchmarkInfo> {
-    BenchmarkInfo::from_names(CATEGORY, NAMES)
+impl GradientBenchmark {
+    fn fixes_extend(&self) -> bool {
+        self.name.starts_with("extend_")
+    }
 }
 
-pub fn run(name: &str, runner: &BenchRunner, level: Level) -> Option<BenchmarkResult> {
-    if !NAMES.contains(&name) {
-        return None;
+const BENCHMARKS: [GradientBenchmark; 9] = [
+    GradientBenchmark { name: "linear_opaque", extend: "pad" },
+    GradientBenchmark { name: "radial_opaque", extend: "pad" },
+    GradientBenchmark { name: "radial_opaque_conical", extend: "pad" },
+    GradientBenchmark { name: "sweep_opaque", extend: "pad" },
+    GradientBenchmark { name: "extend_pad", extend: "pad" },
+    GradientBenchmark { name: "extend_repeat", extend: "repeat" },
+    GradientBenchmark { name: "extend_reflect", extend: "reflect" },
+    GradientBenchmark { name: "many_stops", extend: "repeat" },
+    GradientBenchmark { name: "transparent", extend: "pad" },
+];
+
+pub fn register(registry: &mut Registry) {
+    for benchmark in BENCHMARKS {
+        registry.register(benchmark);
+    }
+}
+
+impl Benchmark for GradientBenchmark {
+    type Input = (Paint, Vec<EncodedPaint>);
+
+    fn category(&self) -> &str {
+        CATEGORY
+    }
+
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn tags(&self) -> &[&str] {
+        match self.name {
+            "transparent" => &["transparent"],
+            name if name.starts_with("extend_") => &["extend"],
+            _ => &[],
+        }
     }
 
-    let blend = BlendMode::new(Mix::Normal, Compose::SrcOver);
+    fn timed(&self) -> &str {
+        "Filling a wide tile with the gradient in the scratch buffer. Encoding the gradient is setup."
+    }
+
+    fn params(&self) -> Vec<ParamInfo> {
+        let mut params = vec![];
+        if !self.fixes_extend() {
+            params.push(ParamInfo::choice("extend", "How the gradient continues past its ends", self.extend, EXTENDS));
+        }
+        if self.name == "many_stops" {
+            params.push(ParamInfo::int("stops", "Number of random color stops", MANY_STOPS, 2, 1024));
+        }
+        params
+    }
+
+    fn setup(&self, params: &Params) -> Result<Self::Input, BenchError> {
+        let extend = if self.fixes_extend() { self.extend } else { params.choice("extend") };
+        let gradient = gradient(self.name, extend, params);
+        let mut paints = vec![];
+        let paint = gradient.encode_into(&mut paints, Affine::IDENTITY);
+        Ok((paint, paints))
+    }
+
+    fn run(&self, (paint, paints): &mut Self::Input, runner: &BenchRunner, level: Level) -> BenchmarkResult {
+        let blend = BlendMode::new(Mix::Normal, Compose::SrcOver);
+        let (paint, paints) = (&*paint, &*paints);
+
+        let simd_variant = level_suffix(level);
+
+        dispatch!(level, simd => {
+            let mut fine = Fine::<_, U8Kernel>::new(simd);
+
+            let result = runner.run(
+                &self.id(),
+                CATEGORY,
+                self.name,
+                simd_variant,
+                #[inline(always)]
+                || {
+                    fine.fill(0, WideTile::WIDTH as usize, paint, blend, paints, None, None);
+                    std::hint::black_box(&fine);
+                },
+            )
+            .with_throughput(ThroughputUnit::Pixels, u64::from(WideTile::WIDTH) * u64::from(Tile::HEIGHT));
+            runner.record_output("scratch", || scratch_pixels(&fine));
+            result
+        })
+    }
+}
 
+fn gradient(name: &str, extend: &str, params: &Params) -> Gradient {
     let opaque_stops = || {
         ColorStops(smallvec![
             ColorStop { offset: 0.0, color: DynamicColor::from_alpha_color(BLUE) },
@@ -52,14 +137,13 @@ pub fn run(name: &str, runner: &BenchRunner, level: Level) -> Option<BenchmarkRe
         ])
     };
 
-    let (stops, kind, extend): (ColorStops, GradientKind, vello_common::peniko::Extend) = match name {
+    let (stops, kind): (ColorStops, GradientKind) = match name {
         "linear_opaque" => (
             opaque_stops(),
             LinearGradientPosition {
                 start: Point::new(128.0, 128.0),
                 end: Point::new(134.0, 134.0),
             }.into(),
-            vello_common::peniko::Extend::Pad,
         ),
         "radial_opaque" => (
             opaque_stops(),
@@ -69,7 +153,6 @@ pub fn run(name: &str, runner: &BenchRunner, level: Level) -> Option<BenchmarkRe
                 end_center: Point::new(WideTile::WIDTH as f64 / 2.0, (Tile::HEIGHT / 2) as f64),
                 end_radius: 75.0,
             }.into(),
-            vello_common::peniko::Extend::Pad,
         ),
         "radial_opaque_conical" => (
             opaque_stops(),
@@ -82,7 +165,6 @@ pub fn run(name: &str, runner: &BenchRunner, level: Level) -> Option<BenchmarkRe
                 ),
                 end_radius: 75.0,
             }.into(),
-            vello_common::peniko::Extend::Pad,
         ),
         "sweep_opaque" => (
             opaque_stops(),
@@ -91,7 +173,6 @@ pub fn run(name: &str, runner: &BenchRunner, level: Level) -> Option<BenchmarkRe
                 start_angle: 70.0_f32.to_radians(),
                 end_angle: 250.0_f32.to_radians(),
             }.into(),
-            vello_common::peniko::Extend::Pad,
         ),
         "extend_pad" => (
             opaque_stops(),
@@ -99,7 +180,6 @@ pub fn run(name: &str, runner: &BenchRunner, level: Level) -> Option<BenchmarkRe
                 start: Point::new(128.0, 128.0),
                 end: Point::new(134.0, 134.0),
             }.into(),
-            vello_common::peniko::Extend::Pad,
         ),
         "extend_repeat" => (
             opaque_stops(),
@@ -107,7 +187,6 @@ pub fn run(name: &str, runner: &BenchRunner, level: Level) -> Option<BenchmarkRe
                 start: Point::new(128.0, 128.0),
                 end: Point::new(134.0, 134.0),
             }.into(),
-            vello_common::peniko::Extend::Repeat,
         ),
         "extend_reflect" => (
             opaque_stops(),
@@ -115,13 +194,12 @@ pub fn run(name: &str, runner: &BenchRunner, level: Level) -> Option<BenchmarkRe
                 start: Point::new(128.0, 128.0),
                 end: Point::new(134.0, 134.0),
             }.into(),
-            vello_common::peniko::Extend::Reflect,
         ),
         "many_stops" => {
             let mut vec = SmallVec::new();
             let mut rng = StdRng::from_seed(SEED);
-            let max = 120;
-            for i in 0..=120 {
+            let max = params.int("stops") - 1;
+            for i in 0..=max {
                 let offset = i as f32 / max as f32;
                 let color = DynamicColor::from_alpha_color(AlphaColor::<Srgb>::new([
                     rng.random::<f32>(),
@@ -137,7 +215,6 @@ pub fn run(name: &str, runner: &BenchRunner, level: Level) -> Option<BenchmarkRe
                     start: Point::new(128.0, 128.0),
                     end: Point::new(134.0, 134.0),
                 }.into(),
-                vello_common::peniko::Extend::Repeat,
             )
         }
         "transparent" => (
@@ -151,30 +228,10 @@ pub fn run(name: &str, runner: &BenchRunner, level: Level) -> Option<BenchmarkRe
                 start: Point::new(128.0, 128.0),
                 end: Point::new(134.0, 134.0),
             }.into(),
-            vello_common::peniko::Extend::Pad,
         ),
         _ => panic!("unknown fine/gradient benchmark: {name}"),
     };
 
-    let grad = Gradient { kind, stops, extend, ..Default::default() };
-    let mut paints = vec![];
-    let paint = grad.encode_into(&mut paints, Affine::IDENTITY);
-
-    let simd_variant = level_suffix(level);
-
-    Some(dispatch!(level, simd => {
-        let mut fine = Fine::<_, U8Kernel>::new(simd);
-
-        runner.run(
-            &format!("{CATEGORY}/{name}"),
-            CATEGORY,
-            name,
-            simd_variant,
-            #[inline(always)]
-            || {
-                fine.fill(0, WideTile::WIDTH as usize, &paint, blend, &paints, None, None);
-                std::hint::black_box(&fine);
-            },
-        )
-    }))
+    let extend = extend_from_name(extend);
+    Gradient { kind, stops, extend, ..Default::default() }
 }
@@ -1,93 +1,164 @@
 use std::sync::Arc;
 
-use crate::registry::BenchmarkInfo;
-use crate::result::BenchmarkResult;
+use super::{EXTENDS, extend_from_name, scratch_pixels};
+use crate::error::BenchError;
+use crate::params::{ParamInfo, Params};
+use crate::registry::{Benchmark, Registry};
+use crate::result::{BenchmarkResult, ThroughputUnit};
 use crate::runner::BenchRunner;
 use crate::simd::level_suffix;
 use fearless_simd::{Level, dispatch};
 use vello_common::coarse::WideTile;
-use vello_common::encode::EncodeExt;
+use vello_common::encode::{EncodeExt, EncodedPaint};
 use vello_common::kurbo::{Affine, Point};
-use vello_common::paint::{Image, ImageSource};
-use vello_common::peniko::{BlendMode, Compose, Extend, ImageQuality, ImageSampler, Mix};
+use vello_common::paint::{Image, ImageSource, Paint};
+use vello_common::peniko::{BlendMode, Compose, ImageQuality, ImageSampler, Mix};
 use vello_common::pixmap::Pixmap;
 use vello_common::tile::Tile;
 use vello_cpu::fine::{Fine, U8Kernel};
 
-const NAMES: &[&str] = &[
-    "no_transform",
-    "scale",
-    "rotate",
-    "quality_low",
-    "quality_medium",
-    "quality_high",
-    "extend_pad",
-    "extend_repeat",
-    "extend_reflect",
-];
 const CATEGORY: &str = "fine/image";
+const QUALITIES: &[&str] = &["low", "medium", "high"];
+
+/// Image files, with their names for error messages.
+static COLR_DATA: (&str, &[u8]) = ("big_colr.png", include_bytes!("../../../assets/big_colr.png"));
+static SMALL_DATA: (&str, &[u8]) = ("rgb_image_2x2.png", include_bytes!("../../../assets/rgb_image_2x2.png"));
+
+/// An image fill, with the default sampling quality and extend mode of its parameters. The
+/// `quality_*` and `extend_*` benchmarks have no parameter for what their names fix, so that
+/// overrides can't contradict them.
+struct ImageBenchmark {
+    name: &'static str,
+    quality: &'static str,
+    extend: &'static str,
+}
+
+impl ImageBenchmark {
+    fn fixes_quality(&self) -> bool {
+        self.name.starts_with("quality_")
+    }
+
+    fn fixes_extend(&self) -> bool {
+        self.name.starts_with("extend_")
+    }
+}
 
-static COLR_DATA: &[u8] = include_bytes!("../../../assets/big_colr.png");
-static SMALL_DATA: &[u8] = include_bytes!("../../../assets/rgb_image_2x2.png");
+const BENCHMARKS: [ImageBenchmark; 9] = [
+    ImageBenchmark { name: "no_transform", quality: "low", extend: "pad" },
+    ImageBenchmark { name: "scale", quality: "low", extend: "pad" },
+    ImageBenchmark { name: "rotate", quality: "low", extend: "pad" },
+    ImageBenchmark { name: "quality_low", quality: "low", extend: "pad" },
+    ImageBenchmark { name: "quality_medium", quality: "medium", extend: "pad" },
+    ImageBenchmark { name: "quality_high", quality: "high", extend: "pad" },
+    ImageBenchmark { name: "extend_pad", quality: "low", extend: "pad" },
+    ImageBenchmark { name: "extend_repeat", quality: "low", extend: "repeat" },
+    ImageBenchmark { name: "extend_reflect", quality: "low", extend: "reflect" },
+];
 
-pub fn list() -> Vec<BenchmarkInfo> {
-    BenchmarkInfo::from_names(CATEGORY, NAMES)
+pub fn register(registry: &mut Registry) {
+    for benchmark in BENCHMARKS {
+        registry.register(benchmark);
+    }
 }
 
-pub fn run(name: &str, runner: &BenchRunner, level: Level) -> Option<BenchmarkResult> {
-    if !NAMES.contains(&name) {
-        return None;
+impl Benchmark for ImageBenchmark {
+    type Input = (Paint, Vec<EncodedPaint>);
+
+    fn category(&self) -> &str {
+        CATEGORY
+    }
+
+    fn name(&self) -> &str {
+        self.name
     }
 
-    let blend = BlendMode::new(Mix::Normal, Compose::SrcOver);
+    fn tags(&self) -> &[&str] {
+        if self.name.starts_with("extend_") { &["extend"] } else { &[] }
+    }
+
+    fn timed(&self) -> &str {
+        "Filling a wide tile with the image in the scratch buffer. Decoding and encoding the image are setup."
+    }
+
+    fn params(&self) -> Vec<ParamInfo> {
+        let mut params = vec![];
+        if !self.fixes_quality() {
+            params.push(ParamInfo::choice("quality", "Sampling quality", self.quality, QUALITIES));
+        }
+        if !self.fixes_extend() {
+            params.push(ParamInfo::choice("extend", "How the image continues past its edges", self.extend, EXTENDS));
+        }
+        params
+    }
+
+    fn setup(&self, params: &Params) -> Result<Self::Input, BenchError> {
+        let quality = if self.fixes_quality() { self.quality } else { params.choice("quality") };
+        let extend = if self.fixes_extend() { self.extend } else { params.choice("extend") };
+        let (image, transform) = image(self.name, quality, extend)?;
+        let mut paints = vec![];
+        let paint = image.encode_into(&mut paints, transform);
+        Ok((paint, paints))
+    }
 
+    fn run(&self, (paint, paints): &mut Self::Input, runner: &BenchRunner, level: Level) -> BenchmarkResult {
+        let blend = BlendMode::new(Mix::Normal, Compose::SrcOver);
+        let (paint, paints) = (&*paint, &*paints);
+
+        let simd_variant = level_suffix(level);
+
+        dispatch!(level, simd => {
+            let mut fine = Fine::<_, U8Kernel>::new(simd);
+
+            let result = runner.run(
+                &self.id(),
+                CATEGORY,
+                self.name,
+                simd_variant,
+                #[inline(always)]
+                || {
+                    fine.fill(0, WideTile::WIDTH as usize, paint, blend, paints, None, None);
+                    std::hint::black_box(&fine);
+                },
+            )
+            .with_throughput(ThroughputUnit::Pixels, u64::from(WideTile::WIDTH) * u64::from(Tile::HEIGHT));
+            runner.record_output("scratch", || scratch_pixels(&fine));
+            result
+        })
+    }
+}
+
+/// The image to draw and its transform. Fails if the PNG can't be decoded.
+fn image(name: &str, quality: &str, extend: &str) -> Result<(Image, Affine), BenchError> {
     let small_translate = Affine::translate((WideTile::WIDTH as f64 / 2.0, 0.0));
 
-    let (quality, extend, data, transform): (ImageQuality, Extend, &[u8], Affine) = match name {
-        "no_transform" => (ImageQuality::Low, Extend::Pad, COLR_DATA, Affine::IDENTITY),
-        "scale" => (ImageQuality::Low, Extend::Pad, COLR_DATA, Affine::scale(3.0)),
+    let ((file, data), transform): ((&str, &[u8]), Affine) = match name {
+        "no_transform" => (COLR_DATA, Affine::IDENTITY),
+        "scale" => (COLR_DATA, Affine::scale(3.0)),
         "rotate" => (
-            ImageQuality::Low,
-            Extend::Pad,
             COLR_DATA,
             Affine::rotate_about(
                 1.0,
                 Point::new(WideTile::WIDTH as f64 / 2.0, Tile::HEIGHT as f64 / 2.0),
             ),
         ),
-        "quality_low" => (ImageQuality::Low, Extend::Pad, COLR_DATA, Affine::scale(3.0)),
-        "quality_medium" => (ImageQuality::Medium, Extend::Pad, COLR_DATA, Affine::scale(3.0)),
-        "quality_high" => (ImageQuality::High, Extend::Pad, COLR_DATA, Affine::scale(3.0)),
-        "extend_pad" => (ImageQuality::Low, Extend::Pad, SMALL_DATA, small_translate),
-        "extend_repeat" => (ImageQuality::Low, Extend::Repeat, SMALL_DATA, small_translate),
-        "extend_reflect" => (ImageQuality::Low, Extend::Reflect, SMALL_DATA, small_translate),
+        "quality_low" | "quality_medium" | "quality_high" => (COLR_DATA, Affine::scale(3.0)),
+        "extend_pad" | "extend_repeat" | "extend_reflect" => (SMALL_DATA, small_translate),
         _ => panic!("unknown fine/image benchmark: {name}"),
     };
+    let quality = match quality {
+        "low" => ImageQuality::Low,
+        "medium" => ImageQuality::Medium,
+        "high" => ImageQuality::High,
+        quality => panic!("unknown image quality: {quality}"),
+    };
+    let extend = extend_from_name(extend);
 
-    let pixmap = Pixmap::from_png(data).unwrap();
+    let pixmap = Pixmap::from_png(data)
+        .map_err(|e| BenchError::Asset { name: file.to_string(), message: e.to_string() })?;
     let image = Image {
         image: ImageSource::Pixmap(Arc::new(pixmap)),
         sampler: ImageSampler { x_extend: extend, y_extend: extend, quality, alpha: 1.0 },
     };
 
-    let mut paints = vec![];
-    let paint = image.encode_into(&mut paints, transform);
-
-    let simd_variant = level_suffix(level);
-
-    Some(dispatch!(level, simd => {
-        let mut fine = Fine::<_, U8Kernel>::new(simd);
-
-        runner.run(
-            &format!("{CATEGORY}/{name}"),
-            CATEGORY,
-            name,
-            simd_variant,
-            #[inline(always)]
-            || {
-                fine.fill(0, WideTile::WIDTH as usize, &paint, blend, &paints, None, None);
-                std::hint::black_box(&fine);
-            },
-        )
-    }))
+    Ok((image, transform))
 }
@@ -3,3 +3,39 @@ pub mod gradient;
 pub mod image;
 pub mod pack;
 pub mod strip;
+
+use crate::registry::Registry;
+use fearless_simd::Simd;
+use vello_common::coarse::WideTile;
+use vello_common::peniko::Extend;
+use vello_common::tile::Tile;
+use vello_cpu::fine::{Fine, SCRATCH_BUF_SIZE, U8Kernel};
+use vello_cpu::region::Regions;
+
+/// Names of the extend modes, for parameters.
+const EXTENDS: &[&str] = &["pad", "repeat", "reflect"];
+
+fn extend_from_name(name: &str) -> Extend {
+    match name {
+        "pad" => Extend::Pad,
+        "repeat" => Extend::Repeat,
+        "reflect" => Extend::Reflect,
+        _ => panic!("unknown extend mode: {name}"),
+    }
+}
+
+/// The scratch buffer of `fine` packed into RGBA8 pixels, as recorded for validation.
+fn scratch_pixels<S: Simd>(fine: &Fine<S, U8Kernel>) -> Vec<u8> {
+    let mut buf = vec![0; SCRATCH_BUF_SIZE];
+    Regions::new(WideTile::WIDTH, Tile::HEIGHT, &mut buf).update_regions(|region| fine.pack(region));
+    buf
+}
+
+/// Register the benchmarks of every `fine` category.
+pub fn register(registry: &mut Registry) {
+    fill::register(registry);
+    gradient::register(registry);
+    image::register(registry);
+    pack::register(registry);
+    strip::register(registry);
+}
@@ -1,5 +1,7 @@
-use crate::registry::BenchmarkInfo;
-use crate::result::BenchmarkResult;
+use crate::error::BenchError;
+use crate::params::{ParamInfo, Params};
+use crate::registry::{Benchmark, Registry};
+use crate::result::{BenchmarkResult, ThroughputUnit};
 use crate::runner::BenchRunner;
 use crate::simd::level_suffix;
 use fearless_simd::{Level, dispatch};
@@ -8,43 +10,76 @@ use vello_common::tile::Tile;
 use vello_cpu::fine::{Fine, U8Kernel, SCRATCH_BUF_SIZE};
 use vello_cpu::region::Regions;
 
-const NAMES: &[&str] = &["block", "regular"];
 const CATEGORY: &str = "fine/pack";
 
-pub fn list() -> Vec<BenchmarkInfo> {
-    BenchmarkInfo::from_names(CATEGORY, NAMES)
+/// Packing of a region with the default width of its parameter.
+struct PackBenchmark {
+    name: &'static str,
+    width: u16,
 }
 
-pub fn run(name: &str, runner: &BenchRunner, level: Level) -> Option<BenchmarkResult> {
-    if !NAMES.contains(&name) {
-        return None;
+const BENCHMARKS: [PackBenchmark; 2] = [
+    PackBenchmark { name: "block", width: WideTile::WIDTH },
+    PackBenchmark { name: "regular", width: WideTile::WIDTH - 1 },
+];
+
+pub fn register(registry: &mut Registry) {
+    for benchmark in BENCHMARKS {
+        registry.register(benchmark);
+    }
+}
+
+impl Benchmark for PackBenchmark {
+    /// The region width.
+    type Input = u16;
+
+    fn category(&self) -> &str {
+        CATEGORY
+    }
+
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn timed(&self) -> &str {
+        "Packing the scratch buffer into the regions of an output buffer. Building the regions is setup."
     }
 
-    let width = match name {
-        "block" => WideTile::WIDTH,
-        "regular" => WideTile::WIDTH - 1,
-        _ => panic!("unknown fine/pack benchmark: {name}"),
-    };
-
-    let simd_variant = level_suffix(level);
-
-    Some(dispatch!(level, simd => {
-        let fine = Fine::<_, U8Kernel>::new(simd);
-        let mut buf = vec![0; SCRATCH_BUF_SIZE];
-
-        runner.run(
-            &format!("{CATEGORY}/{name}"),
-            CATEGORY,
-            name,
-            simd_variant,
-            #[inline(always)]
-            || {
-                let mut regions = Regions::new(width, Tile::HEIGHT, &mut buf);
-                regions.update_regions(|region| {
-                    fine.pack(region);
-                });
-                std::hint::black_box(&regions);
-            },
-        )
-    }))
+    fn params(&self) -> Vec<ParamInfo> {
+        vec![ParamInfo::int("width", "Width of the region in pixels", self.width.into(), 1, WideTile::WIDTH.into())]
+    }
+
+    fn setup(&self, params: &Params) -> Result<u16, BenchError> {
+        Ok(params.int("width") as u16)
+    }
+
+    fn run(&self, width: &mut u16, runner: &BenchRunner, level: Level) -> BenchmarkResult {
+        let width = *width;
+
+        let simd_variant = level_suffix(level);
+
+        dispatch!(level, simd => {
+            let fine = Fine::<_, U8Kernel>::new(simd);
+            let mut buf = vec![0; SCRATCH_BUF_SIZE];
+            // The regions only borrow `buf`, so they are built once and only packing is timed.
+            let mut regions = Regions::new(width, Tile::HEIGHT, &mut buf);
+
+            let result = runner.run(
+                &self.id(),
+                CATEGORY,
+                self.name,
+                simd_variant,
+                #[inline(always)]
+                || {
+                    regions.update_regions(|region| {
+                        fine.pack(region);
+                    });
+                    std::hint::black_box(&regions);
+                },
+            )
+            .with_throughput(ThroughputUnit::Pixels, u64::from(width) * u64::from(Tile::HEIGHT));
+            runner.record_output("packed", || buf);
+            result
+        })
+    }
 }
@@ -1,5 +1,8 @@
-use crate::registry::BenchmarkInfo;
-use crate::result::BenchmarkResult;
+use super::scratch_pixels;
+use crate::error::BenchError;
+use crate::params::{ParamInfo, Params};
+use crate::registry::{Benchmark, Registry};
+use crate::result::{BenchmarkResult, ThroughputUnit};
 use crate::runner::BenchRunner;
 use crate::simd::level_suffix;
 use fearless_simd::{Level, dispatch};
@@ -12,48 +15,76 @@ use vello_common::peniko::{BlendMode, Compose, Mix};
 use vello_common::tile::Tile;
 use vello_cpu::fine::{Fine, U8Kernel};
 
-const NAMES: &[&str] = &["solid_short", "solid_long"];
 const CATEGORY: &str = "fine/strip";
 const SEED: [u8; 32] = [0; 32];
 
-pub fn list() -> Vec<BenchmarkInfo> {
-    BenchmarkInfo::from_names(CATEGORY, NAMES)
+/// A strip fill with the default width of its parameter.
+struct StripBenchmark {
+    name: &'static str,
+    width: usize,
 }
 
-pub fn run(name: &str, runner: &BenchRunner, level: Level) -> Option<BenchmarkResult> {
-    if !NAMES.contains(&name) {
-        return None;
+const BENCHMARKS: [StripBenchmark; 2] = [
+    StripBenchmark { name: "solid_short", width: 32 },
+    StripBenchmark { name: "solid_long", width: 256 },
+];
+
+pub fn register(registry: &mut Registry) {
+    for benchmark in BENCHMARKS {
+        registry.register(benchmark);
+    }
+}
+
+impl Benchmark for StripBenchmark {
+    /// Random coverage of a whole wide tile, and the fill width.
+    type Input = (Vec<u8>, usize);
+
+    fn category(&self) -> &str {
+        CATEGORY
+    }
+
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn timed(&self) -> &str {
+        "Filling a strip of the given width through an alpha mask in the scratch buffer. Generating the mask is setup."
     }
 
-    let blend = BlendMode::new(Mix::Normal, Compose::SrcOver);
-    let paint = Paint::Solid(PremulColor::from_alpha_color(ROYAL_BLUE));
-
-    let mut rng = StdRng::from_seed(SEED);
-    let alphas: Vec<u8> = (0..WideTile::WIDTH as usize * Tile::HEIGHT as usize)
-        .map(|_| rng.random())
-        .collect();
-
-    let width = match name {
-        "solid_short" => 32,
-        "solid_long" => 256,
-        _ => panic!("unknown fine/strip benchmark: {name}"),
-    };
-
-    let simd_variant = level_suffix(level);
-
-    Some(dispatch!(level, simd => {
-        let mut fine = Fine::<_, U8Kernel>::new(simd);
-
-        runner.run(
-            &format!("{CATEGORY}/{name}"),
-            CATEGORY,
-            name,
-            simd_variant,
-            #[inline(always)]
-            || {
-                fine.fill(0, width, &paint, blend, &[], Some(&alphas), None);
-                std::hint::black_box(&fine);
-            },
-        )
-    }))
+    fn params(&self) -> Vec<ParamInfo> {
+        vec![ParamInfo::int("width", "Width of the strip in pixels", self.width as i64, 1, WideTile::WIDTH.into())]
+    }
+
+    fn setup(&self, params: &Params) -> Result<Self::Input, BenchError> {
+        let mut rng = StdRng::from_seed(SEED);
+        let alphas = (0..WideTile::WIDTH as usize * Tile::HEIGHT as usize).map(|_| rng.random()).collect();
+        Ok((alphas, params.int("width") as usize))
+    }
+
+    fn run(&self, (alphas, width): &mut Self::Input, runner: &BenchRunner, level: Level) -> BenchmarkResult {
+        let blend = BlendMode::new(Mix::Normal, Compose::SrcOver);
+        let paint = Paint::Solid(PremulColor::from_alpha_color(ROYAL_BLUE));
+        let (alphas, width) = (&*alphas, *width);
+
+        let simd_variant = level_suffix(level);
+
+        dispatch!(level, simd => {
+            let mut fine = Fine::<_, U8Kernel>::new(simd);
+
+            let result = runner.run(
+                &self.id(),
+                CATEGORY,
+                self.name,
+                simd_variant,
+                #[inline(always)]
+                || {
+                    fine.fill(0, width, &paint, blend, &[], Some(alphas), None);
+                    std::hint::black_box(&fine);
+                },
+            )
+            .with_throughput(ThroughputUnit::Pixels, (width * Tile::HEIGHT as usize) as u64);
+            runner.record_output("scratch", || scratch_pixels(&fine));
+            result
+        })
+    }
 }
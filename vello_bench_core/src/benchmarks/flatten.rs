@@ -1,48 +1,98 @@
-use crate::data::get_data_items;
-use crate::registry::BenchmarkInfo;
-use crate::result::BenchmarkResult;
+use crate::data::{DataItem, data_item_names, get_data_item};
+use crate::error::BenchError;
+use crate::params::Params;
+use crate::registry::{Benchmark, Registry};
+use crate::result::{BenchmarkResult, ThroughputUnit};
 use crate::runner::BenchRunner;
 use crate::simd::level_suffix;
 use fearless_simd::Level;
 use vello_common::flatten::{self, FlattenCtx, Line};
-use vello_common::kurbo::Affine;
+use vello_common::kurbo::{Affine, BezPath};
 
 const CATEGORY: &str = "flatten";
 
-pub fn list() -> Vec<BenchmarkInfo> {
-    BenchmarkInfo::from_data_items(CATEGORY)
+struct FlattenBenchmark {
+    name: &'static str,
 }
 
-pub fn run(name: &str, runner: &BenchRunner, level: Level) -> Option<BenchmarkResult> {
-    let items = get_data_items();
-    let item = items.iter().find(|i| i.name == name)?;
-    let expanded_strokes = item.expanded_strokes();
-    let simd_variant = level_suffix(level);
-
-    let mut line_buf: Vec<Line> = vec![];
-    let mut temp_buf: Vec<Line> = vec![];
-    let mut flatten_ctx = FlattenCtx::default();
-
-    Some(runner.run(
-        &format!("{CATEGORY}/{name}"),
-        CATEGORY,
-        name,
-        simd_variant,
-        #[inline(always)]
-        || {
-            line_buf.clear();
-
-            for path in &item.fills {
-                flatten::fill(level, &path.path, path.transform, &mut temp_buf, &mut flatten_ctx);
-                line_buf.extend(&temp_buf);
-            }
-
-            for stroke in &expanded_strokes {
-                flatten::fill(level, stroke, Affine::IDENTITY, &mut temp_buf, &mut flatten_ctx);
-                line_buf.extend(&temp_buf);
-            }
-
-            std::hint::black_box(&line_buf);
-        },
-    ))
+pub fn register(registry: &mut Registry) {
+    for name in data_item_names() {
+        registry.register(FlattenBenchmark { name });
+    }
+}
+
+impl Benchmark for FlattenBenchmark {
+    /// The data item, and its strokes expanded to fills.
+    type Input = (&'static DataItem, Vec<BezPath>);
+
+    fn category(&self) -> &str {
+        CATEGORY
+    }
+
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn tags(&self) -> &[&str] {
+        &["svg"]
+    }
+
+    fn timed(&self) -> &str {
+        "Flattening every fill and expanded stroke into a line buffer, which is reused and cleared each iteration. Expanding the strokes is setup."
+    }
+
+    fn setup(&self, _: &Params) -> Result<Self::Input, BenchError> {
+        let item = get_data_item(self.name)?;
+        Ok((item, item.expanded_strokes()))
+    }
+
+    fn run(&self, (item, expanded_strokes): &mut Self::Input, runner: &BenchRunner, level: Level) -> BenchmarkResult {
+        let (item, expanded_strokes) = (*item, &*expanded_strokes);
+        let simd_variant = level_suffix(level);
+
+        let segments = item.fills.iter().map(|p| p.path.segments().count()).sum::<usize>()
+            + expanded_strokes.iter().map(|p| p.segments().count()).sum::<usize>();
+
+        let mut line_buf: Vec<Line> = vec![];
+        let mut temp_buf: Vec<Line> = vec![];
+        let mut flatten_ctx = FlattenCtx::default();
+
+        let result = runner
+            .run(
+                &self.id(),
+                CATEGORY,
+                &item.name,
+                simd_variant,
+                #[inline(always)]
+                || {
+                    line_buf.clear();
+                    flatten_item(level, item, expanded_strokes, &mut line_buf, &mut temp_buf, &mut flatten_ctx);
+                    std::hint::black_box(&line_buf);
+                },
+            )
+            .with_throughput(ThroughputUnit::Segments, segments as u64);
+        runner.record_output("lines", || line_buf);
+        result
+    }
+}
+
+/// Flatten the fills and the expanded strokes of `item`, appending the lines to `line_buf`.
+#[inline(always)]
+fn flatten_item(
+    level: Level,
+    item: &DataItem,
+    expanded_strokes: &[BezPath],
+    line_buf: &mut Vec<Line>,
+    temp_buf: &mut Vec<Line>,
+    flatten_ctx: &mut FlattenCtx,
+) {
+    for path in &item.fills {
+        flatten::fill(level, &path.path, path.transform, temp_buf, flatten_ctx);
+        line_buf.extend(&*temp_buf);
+    }
+
+    for stroke in expanded_strokes {
+        flatten::fill(level, stroke, Affine::IDENTITY, temp_buf, flatten_ctx);
+        line_buf.extend(&*temp_buf);
+    }
 }
@@ -3,3 +3,14 @@ pub mod flatten;
 pub mod render_strips;
 pub mod strokes;
 pub mod tile;
+
+use crate::registry::Registry;
+
+/// Register the benchmarks of every category.
+pub fn register(registry: &mut Registry) {
+    fine::register(registry);
+    tile::register(registry);
+    flatten::register(registry);
+    strokes::register(registry);
+    render_strips::register(registry);
+}
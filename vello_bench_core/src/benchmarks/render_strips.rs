@@ -1,49 +1,77 @@
-use crate::data::get_data_items;
-use crate::registry::BenchmarkInfo;
-use crate::result::BenchmarkResult;
+use crate::data::{DataItem, data_item_names, get_data_item};
+use crate::error::BenchError;
+use crate::params::Params;
+use crate::registry::{Benchmark, Registry};
+use crate::result::{BenchmarkResult, ThroughputUnit};
 use crate::runner::BenchRunner;
 use crate::simd::level_suffix;
 use fearless_simd::Level;
 use vello_common::peniko::Fill;
+use vello_common::flatten::Line;
 use vello_common::strip::Strip;
+use vello_common::tile::Tiles;
 
 const CATEGORY: &str = "render_strips";
 
-pub fn list() -> Vec<BenchmarkInfo> {
-    BenchmarkInfo::from_data_items(CATEGORY)
+struct RenderStripsBenchmark {
+    name: &'static str,
 }
 
-pub fn run(name: &str, runner: &BenchRunner, level: Level) -> Option<BenchmarkResult> {
-    let items = get_data_items();
-    let item = items.iter().find(|i| i.name == name)?;
-    let lines = item.lines();
-    let tiles = item.sorted_tiles();
-    let simd_variant = level_suffix(level);
-
-    let mut strip_buf: Vec<Strip> = vec![];
-    let mut alpha_buf: Vec<u8> = vec![];
-
-    Some(runner.run(
-        &format!("{CATEGORY}/{name}"),
-        CATEGORY,
-        name,
-        simd_variant,
-        #[inline(always)]
-        || {
-            strip_buf.clear();
-            alpha_buf.clear();
-
-            vello_common::strip::render(
-                level,
-                &tiles,
-                &mut strip_buf,
-                &mut alpha_buf,
-                Fill::NonZero,
-                None,
-                &lines,
-            );
-
-            std::hint::black_box(&strip_buf);
-        },
-    ))
+pub fn register(registry: &mut Registry) {
+    for name in data_item_names() {
+        registry.register(RenderStripsBenchmark { name });
+    }
+}
+
+impl Benchmark for RenderStripsBenchmark {
+    /// The data item, its lines and its sorted tiles.
+    type Input = (&'static DataItem, Vec<Line>, Tiles);
+
+    fn category(&self) -> &str {
+        CATEGORY
+    }
+
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn tags(&self) -> &[&str] {
+        &["svg"]
+    }
+
+    fn timed(&self) -> &str {
+        "Rendering the sorted tiles into strip and alpha buffers, which are reused and cleared each iteration. Flattening and tiling are setup."
+    }
+
+    fn setup(&self, _: &Params) -> Result<Self::Input, BenchError> {
+        let item = get_data_item(self.name)?;
+        Ok((item, item.lines(), item.sorted_tiles()))
+    }
+
+    fn run(&self, (item, lines, tiles): &mut Self::Input, runner: &BenchRunner, level: Level) -> BenchmarkResult {
+        let (item, lines, tiles) = (*item, &*lines, &*tiles);
+        let simd_variant = level_suffix(level);
+
+        let mut strip_buf: Vec<Strip> = vec![];
+        let mut alpha_buf: Vec<u8> = vec![];
+
+        let result = runner.run(
+            &self.id(),
+            CATEGORY,
+            &item.name,
+            simd_variant,
+            #[inline(always)]
+            || {
+                strip_buf.clear();
+                alpha_buf.clear();
+                vello_common::strip::render(level, tiles, &mut strip_buf, &mut alpha_buf, Fill::NonZero, None, lines);
+                std::hint::black_box((&strip_buf, &alpha_buf));
+            },
+        );
+        let result = result.with_throughput(ThroughputUnit::Strips, strip_buf.len() as u64);
+
+        runner.record_output("strips", || strip_buf);
+        runner.record_output("alphas", || alpha_buf);
+        result
+    }
 }
@@ -1,6 +1,8 @@
-use crate::data::get_data_items;
-use crate::registry::BenchmarkInfo;
-use crate::result::BenchmarkResult;
+use crate::data::{DataItem, data_item_names, get_data_item};
+use crate::error::BenchError;
+use crate::params::Params;
+use crate::registry::{Benchmark, Registry};
+use crate::result::{BenchmarkResult, ThroughputUnit};
 use crate::runner::BenchRunner;
 use crate::simd::level_suffix;
 use fearless_simd::Level;
@@ -9,37 +11,70 @@ use vello_common::kurbo::{Stroke, StrokeCtx};
 
 const CATEGORY: &str = "strokes";
 
-pub fn list() -> Vec<BenchmarkInfo> {
-    BenchmarkInfo::from_data_items(CATEGORY)
+struct StrokesBenchmark {
+    name: &'static str,
 }
 
-pub fn run(name: &str, runner: &BenchRunner, level: Level) -> Option<BenchmarkResult> {
-    let items = get_data_items();
-    let item = items.iter().find(|i| i.name == name)?;
-    let simd_variant = level_suffix(level);
-
-    // Strokes don't use SIMD level directly.
-    let _ = level;
-
-    let mut stroke_ctx = StrokeCtx::default();
-    let mut paths = vec![];
-
-    Some(runner.run(
-        &format!("{CATEGORY}/{name}"),
-        CATEGORY,
-        name,
-        simd_variant,
-        #[inline(always)]
-        || {
-            paths.clear();
-
-            for path in &item.strokes {
-                let stroke = Stroke { width: path.stroke_width as f64, ..Default::default() };
-                flatten::expand_stroke(path.path.iter(), &stroke, 0.25, &mut stroke_ctx);
-                paths.push(stroke_ctx.output().clone());
-            }
-
-            std::hint::black_box(&paths);
-        },
-    ))
+pub fn register(registry: &mut Registry) {
+    for name in data_item_names() {
+        registry.register(StrokesBenchmark { name });
+    }
+}
+
+impl Benchmark for StrokesBenchmark {
+    type Input = &'static DataItem;
+
+    fn category(&self) -> &str {
+        CATEGORY
+    }
+
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn tags(&self) -> &[&str] {
+        &["svg"]
+    }
+
+    fn timed(&self) -> &str {
+        "Expanding every stroke and copying the result out of the stroke context. Allocating the vector of output paths is setup."
+    }
+
+    // Stroke expansion is scalar code, whatever the level.
+    fn level_agnostic(&self) -> bool {
+        true
+    }
+
+    fn setup(&self, _: &Params) -> Result<Self::Input, BenchError> {
+        get_data_item(self.name)
+    }
+
+    fn run(&self, item: &mut Self::Input, runner: &BenchRunner, level: Level) -> BenchmarkResult {
+        let item = *item;
+        let simd_variant = level_suffix(level);
+
+        let segments: usize = item.strokes.iter().map(|p| p.path.segments().count()).sum();
+        let mut stroke_ctx = StrokeCtx::default();
+
+        // The output paths are allocated in setup and dropped after timing, so only the
+        // expansion and the copy out of the stroke context are measured.
+        runner.run_batched(
+            &self.id(),
+            CATEGORY,
+            &item.name,
+            simd_variant,
+            || Vec::with_capacity(item.strokes.len()),
+            #[inline(always)]
+            |mut paths| {
+                for path in &item.strokes {
+                    let stroke = Stroke { width: path.stroke_width as f64, ..Default::default() };
+                    flatten::expand_stroke(path.path.iter(), &stroke, 0.25, &mut stroke_ctx);
+                    paths.push(stroke_ctx.output().clone());
+                }
+
+                paths
+            },
+        )
+        .with_throughput(ThroughputUnit::Segments, segments as u64)
+    }
 }
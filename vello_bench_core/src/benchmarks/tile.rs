@@ -1,34 +1,74 @@
-use crate::data::get_data_items;
-use crate::registry::BenchmarkInfo;
-use crate::result::BenchmarkResult;
+use crate::data::{DataItem, data_item_names, get_data_item};
+use crate::error::BenchError;
+use crate::params::Params;
+use crate::registry::{Benchmark, Registry};
+use crate::result::{BenchmarkResult, ThroughputUnit};
 use crate::runner::BenchRunner;
 use crate::simd::level_suffix;
 use fearless_simd::Level;
+use vello_common::flatten::Line;
 use vello_common::tile::Tiles;
 
 const CATEGORY: &str = "tile";
 
-pub fn list() -> Vec<BenchmarkInfo> {
-    BenchmarkInfo::from_data_items(CATEGORY)
+struct TileBenchmark {
+    name: &'static str,
 }
 
-pub fn run(name: &str, runner: &BenchRunner, level: Level) -> Option<BenchmarkResult> {
-    let items = get_data_items();
-    let item = items.iter().find(|i| i.name == name)?;
-    let lines = item.lines();
-    let simd_variant = level_suffix(level);
-
-    let mut tiles = Tiles::new(level);
-
-    Some(runner.run(
-        &format!("{CATEGORY}/{name}"),
-        CATEGORY,
-        name,
-        simd_variant,
-        #[inline(always)]
-        || {
-            tiles.make_tiles_analytic_aa(&lines, item.width, item.height);
-            std::hint::black_box(&tiles);
-        },
-    ))
+pub fn register(registry: &mut Registry) {
+    for name in data_item_names() {
+        registry.register(TileBenchmark { name });
+    }
+}
+
+impl Benchmark for TileBenchmark {
+    /// The data item and its lines.
+    type Input = (&'static DataItem, Vec<Line>);
+
+    fn category(&self) -> &str {
+        CATEGORY
+    }
+
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn tags(&self) -> &[&str] {
+        &["svg"]
+    }
+
+    fn timed(&self) -> &str {
+        "Generating the tiles of the flattened lines. Flattening is setup."
+    }
+
+    fn setup(&self, _: &Params) -> Result<Self::Input, BenchError> {
+        let item = get_data_item(self.name)?;
+        Ok((item, item.lines()))
+    }
+
+    fn run(&self, (item, lines): &mut Self::Input, runner: &BenchRunner, level: Level) -> BenchmarkResult {
+        let item = *item;
+        let simd_variant = level_suffix(level);
+
+        let mut tiles = Tiles::new(level);
+
+        let result = runner.run(
+            &self.id(),
+            CATEGORY,
+            &item.name,
+            simd_variant,
+            #[inline(always)]
+            || {
+                tiles.make_tiles_analytic_aa(lines, item.width, item.height);
+                std::hint::black_box(&tiles);
+            },
+        )
+        .with_throughput(ThroughputUnit::Lines, lines.len() as u64);
+        // Tiles can only be read once sorted, which doesn't depend on the level.
+        runner.record_output("tiles", || {
+            tiles.sort_tiles();
+            tiles.iter().copied().collect::<Vec<_>>()
+        });
+        result
+    }
 }
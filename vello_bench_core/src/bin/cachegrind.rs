@@ -0,0 +1,93 @@
+//! Count instructions and cache misses of the benchmarks under Cachegrind.
+//!
+//! Usage: `cachegrind [--iterations N] [--level SUFFIX] [--json] [SELECTION...]`
+//!
+//! Runs every benchmark matched by the selection (all benchmarks if none is given; see
+//! `vello_bench_core::selection` for the syntax, e.g. `fine/fill/* !*_long`) under
+//! `valgrind --tool=cachegrind` and prints the counts per iteration. With `--json`, prints
+//! one `CachegrindResult` per line instead of a table.
+
+use std::path::Path;
+use std::process::ExitCode;
+use vello_bench_core::cachegrind::{self, DEFAULT_ITERATIONS};
+use vello_bench_core::cli::{self, ArgParser};
+use vello_bench_core::{BenchError, available_levels, level_from_suffix};
+
+const USAGE: &str = "cachegrind [--iterations N] [--level SUFFIX] [--json] [SELECTION...]";
+
+struct Args {
+    run: Option<String>,
+    level: Option<String>,
+    iterations: usize,
+    json: bool,
+    selection: Vec<String>,
+}
+
+fn parse_args() -> Result<Args, BenchError> {
+    let mut args = Args { run: None, level: None, iterations: DEFAULT_ITERATIONS, json: false, selection: Vec::new() };
+    let mut parser = ArgParser::from_env();
+
+    while let Some(option) = parser.next_option() {
+        match option.as_str() {
+            "--run" => args.run = Some(parser.value(&option)?),
+            "--level" => args.level = Some(parser.value(&option)?),
+            "--iterations" => args.iterations = parser.value(&option)?,
+            "--json" => args.json = true,
+            _ => return Err(cli::unknown_option(&option)),
+        }
+    }
+
+    args.selection = parser.positional();
+    Ok(args)
+}
+
+fn main() -> ExitCode {
+    cli::main(USAGE, || {
+        let args = parse_args()?;
+        let level = match &args.level {
+            Some(suffix) => level_from_suffix(suffix)?,
+            None => available_levels()[0],
+        };
+
+        // Child process: run the benchmark itself, under Cachegrind.
+        if let Some(id) = &args.run {
+            cachegrind::run_in_process(id, level, args.iterations)?;
+            return Ok(ExitCode::SUCCESS);
+        }
+
+        let exe = std::env::current_exe().map_err(|e| BenchError::InvalidInput {
+            what: "executable path".to_string(),
+            message: e.to_string(),
+        })?;
+        run_all(&exe, &args, level)
+    })
+}
+
+fn run_all(exe: &Path, args: &Args, level: fearless_simd::Level) -> Result<ExitCode, BenchError> {
+    let benchmarks = cli::select(&args.selection)?;
+
+    if !args.json {
+        println!("{:<45} {:>16} {:>12} {:>12}", "benchmark", "instructions", "L1 misses", "LL misses");
+    }
+
+    let mut failed = false;
+    for bench in &benchmarks {
+        match cachegrind::run_under_cachegrind(exe, &bench.id, level, args.iterations) {
+            Ok(result) if args.json => {
+                println!("{}", serde_json::to_string(&result).expect("result serializes"));
+            }
+            Ok(result) => {
+                println!(
+                    "{:<45} {:>16.0} {:>12.1} {:>12.1}",
+                    result.id, result.instructions, result.l1_misses, result.ll_misses
+                );
+            }
+            Err(e) => {
+                eprintln!("{e}");
+                failed = true;
+            }
+        }
+    }
+
+    Ok(if failed { ExitCode::FAILURE } else { ExitCode::SUCCESS })
+}
@@ -0,0 +1,99 @@
+//! Compare two saved result sets.
+//!
+//! Usage: `compare [--significance P] [--noise-threshold FRACTION] [--json] [--fail-on-slower] BASELINE CURRENT`
+//!
+//! Reads two exported or saved reference files and prints, for every benchmark and SIMD
+//! variant present in both with the same parameters, the change of the mean and the verdict,
+//! followed by the current results that couldn't be compared. With `--json`, prints one
+//! `Comparison` or `Unmatched` per line instead of a table. With `--fail-on-slower`, exits
+//! with an error if any benchmark got slower.
+
+use std::process::ExitCode;
+use vello_bench_core::cli::{self, ArgParser};
+use vello_bench_core::{BenchError, CompareOptions, ResultSet, UnmatchedReason, Verdict, compare_sets};
+
+const USAGE: &str =
+    "compare [--significance P] [--noise-threshold FRACTION] [--json] [--fail-on-slower] BASELINE CURRENT";
+
+struct Args {
+    options: CompareOptions,
+    json: bool,
+    fail_on_slower: bool,
+    files: Vec<String>,
+}
+
+fn parse_args() -> Result<Args, BenchError> {
+    let mut args =
+        Args { options: CompareOptions::default(), json: false, fail_on_slower: false, files: Vec::new() };
+    let mut parser = ArgParser::from_env();
+
+    while let Some(option) = parser.next_option() {
+        match option.as_str() {
+            "--significance" => args.options.significance = parser.value(&option)?,
+            "--noise-threshold" => args.options.noise_threshold = parser.value(&option)?,
+            "--json" => args.json = true,
+            "--fail-on-slower" => args.fail_on_slower = true,
+            _ => return Err(cli::unknown_option(&option)),
+        }
+    }
+
+    args.files = parser.positional();
+    if args.files.len() != 2 {
+        return Err(cli::invalid_arguments("expected a baseline and a current result file".to_string()));
+    }
+    Ok(args)
+}
+
+fn load(path: &str) -> Result<ResultSet, BenchError> {
+    let error = |message: String| BenchError::InvalidInput { what: format!("result file {path}"), message };
+    let json = std::fs::read_to_string(path).map_err(|e| error(e.to_string()))?;
+    ResultSet::from_json(&json).map_err(|e| error(e.to_string()))
+}
+
+fn main() -> ExitCode {
+    cli::main(USAGE, run)
+}
+
+fn run() -> Result<ExitCode, BenchError> {
+    let args = parse_args()?;
+    let baseline = load(&args.files[0])?;
+    let current = load(&args.files[1])?;
+
+    let set = compare_sets(&baseline.results, &current.results, &args.options);
+
+    if !args.json {
+        println!("{:<45} {:<8} {:>10} {:>10} {:>10}", "benchmark", "simd", "change", "p-value", "verdict");
+    }
+    for c in &set.comparisons {
+        if args.json {
+            println!("{}", serde_json::to_string(c).expect("comparison serializes"));
+        } else {
+            let p_value = c.p_value.map_or_else(|| "-".to_string(), |p| format!("{p:.4}"));
+            let verdict = match c.verdict {
+                Verdict::Faster => "faster",
+                Verdict::Slower => "slower",
+                Verdict::Unchanged => "unchanged",
+            };
+            println!("{:<45} {:<8} {:>+9.2}% {:>10} {:>10}", c.id, c.simd_variant, c.percent_change, p_value, verdict);
+        }
+    }
+
+    for u in &set.unmatched {
+        if args.json {
+            println!("{}", serde_json::to_string(u).expect("unmatched result serializes"));
+        } else {
+            let reason = match u.reason {
+                UnmatchedReason::NoBaseline => "no baseline",
+                UnmatchedReason::ParamsDiffer => "params differ",
+                UnmatchedReason::EmptyBaseline => "empty baseline",
+            };
+            println!("{:<45} {:<8} {:>10} {:>10} {:>10}", u.id, u.simd_variant, "-", "-", reason);
+        }
+    }
+
+    if args.fail_on_slower && set.comparisons.iter().any(|c| c.verdict == Verdict::Slower) {
+        Ok(ExitCode::FAILURE)
+    } else {
+        Ok(ExitCode::SUCCESS)
+    }
+}
@@ -0,0 +1,90 @@
+//! Run benchmarks at every SIMD level and print their speedups.
+//!
+//! Usage: `matrix [--calibration-ms N] [--measurement-ms N] [--json] [SELECTION...]`
+//!
+//! Runs every benchmark matched by the selection (all benchmarks if none is given; see
+//! `vello_bench_core::selection` for the syntax) at every available SIMD level, and prints
+//! the mean of each level with its speedup over scalar, marking the best level with `*`.
+//! Level-agnostic benchmarks only run at scalar, and show `-` at the other levels. With
+//! `--json`, prints the `LevelMatrix` instead.
+
+use std::process::ExitCode;
+use vello_bench_core::cli::{self, ArgParser};
+use vello_bench_core::{BenchError, BenchRunner, Params, run_level_matrix};
+
+const USAGE: &str = "matrix [--calibration-ms N] [--measurement-ms N] [--json] [SELECTION...]";
+
+struct Args {
+    calibration_ms: u64,
+    measurement_ms: u64,
+    json: bool,
+    selection: Vec<String>,
+}
+
+fn parse_args() -> Result<Args, BenchError> {
+    let mut args = Args { calibration_ms: 100, measurement_ms: 250, json: false, selection: Vec::new() };
+    let mut parser = ArgParser::from_env();
+
+    while let Some(option) = parser.next_option() {
+        match option.as_str() {
+            "--calibration-ms" => args.calibration_ms = parser.value(&option)?,
+            "--measurement-ms" => args.measurement_ms = parser.value(&option)?,
+            "--json" => args.json = true,
+            _ => return Err(cli::unknown_option(&option)),
+        }
+    }
+
+    args.selection = parser.positional();
+    Ok(args)
+}
+
+fn main() -> ExitCode {
+    cli::main(USAGE, run)
+}
+
+fn run() -> Result<ExitCode, BenchError> {
+    let args = parse_args()?;
+    let selection = cli::selection(&args.selection)?;
+
+    let runner = BenchRunner::new(args.calibration_ms, args.measurement_ms);
+    let matrix = run_level_matrix(&runner, &selection, &Params::new())?;
+
+    if args.json {
+        println!("{}", serde_json::to_string(&matrix).expect("matrix serializes"));
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    print!("{:<45}", "benchmark");
+    for level in &matrix.levels {
+        print!(" {level:>22}");
+    }
+    println!();
+
+    let mut failed = false;
+    for row in &matrix.rows {
+        print!("{:<45}", row.id);
+        for cell in &row.cells {
+            let text = match (&cell.result, cell.speedup_vs_scalar) {
+                (Some(result), speedup) => {
+                    let best = if row.best_level.as_ref() == Some(&cell.simd_level) { "*" } else { " " };
+                    let speedup = speedup.map_or_else(|| "-".to_string(), |s| format!("{s:.2}x"));
+                    format!("{:.0} ns {speedup:>6}{best}", result.statistics.mean_ns)
+                }
+                (None, _) if cell.error.is_none() => "-".to_string(),
+                (None, _) => {
+                    failed = true;
+                    "failed".to_string()
+                }
+            };
+            print!(" {text:>22}");
+        }
+        println!();
+        for cell in &row.cells {
+            if let Some(error) = &cell.error {
+                eprintln!("{} ({}): {error}", row.id, cell.simd_level);
+            }
+        }
+    }
+
+    Ok(if failed { ExitCode::FAILURE } else { ExitCode::SUCCESS })
+}
@@ -0,0 +1,87 @@
+//! Check that every SIMD level computes the same outputs as scalar.
+//!
+//! Usage: `validate [--json] [SELECTION...]`
+//!
+//! Runs every benchmark matched by the selection (all benchmarks if none is given; see
+//! `vello_bench_core::selection` for the syntax) once per available SIMD level, and prints
+//! the first element where a level's output differs from scalar. With `--json`, prints one
+//! `Validation` per line instead. Exits with an error if any level differs.
+
+use std::process::ExitCode;
+use vello_bench_core::cli::{self, ArgParser};
+use vello_bench_core::{BenchError, Params, validate_benchmark};
+
+const USAGE: &str = "validate [--json] [SELECTION...]";
+
+struct Args {
+    json: bool,
+    selection: Vec<String>,
+}
+
+fn parse_args() -> Result<Args, BenchError> {
+    let mut args = Args { json: false, selection: Vec::new() };
+    let mut parser = ArgParser::from_env();
+
+    while let Some(option) = parser.next_option() {
+        match option.as_str() {
+            "--json" => args.json = true,
+            _ => return Err(cli::unknown_option(&option)),
+        }
+    }
+
+    args.selection = parser.positional();
+    Ok(args)
+}
+
+fn main() -> ExitCode {
+    cli::main(USAGE, run)
+}
+
+fn run() -> Result<ExitCode, BenchError> {
+    let args = parse_args()?;
+    let benchmarks = cli::select(&args.selection)?;
+
+    if !args.json {
+        println!("{:<45} {:<8} result", "benchmark", "simd");
+    }
+
+    let mut failed = false;
+    for bench in &benchmarks {
+        let validation = match validate_benchmark(&bench.id, &Params::new()) {
+            Ok(validation) => validation,
+            Err(e) => {
+                eprintln!("{e}");
+                failed = true;
+                continue;
+            }
+        };
+        failed |= !validation.passed();
+
+        if args.json {
+            println!("{}", serde_json::to_string(&validation).expect("validation serializes"));
+            continue;
+        }
+        if validation.level_agnostic {
+            println!("{:<45} {:<8} level-agnostic", validation.id, "-");
+        } else if validation.outputs.is_empty() {
+            println!("{:<45} {:<8} no outputs recorded", validation.id, "-");
+        }
+        for level in &validation.levels {
+            let result = match &level.mismatch {
+                None if validation.outputs.is_empty() => continue,
+                None => "ok".to_string(),
+                Some(m) => format!(
+                    "{} differ(s) in {}, first at [{}]: expected {}, got {}",
+                    m.count,
+                    m.output,
+                    m.index,
+                    m.expected.as_deref().unwrap_or("nothing"),
+                    m.actual.as_deref().unwrap_or("nothing"),
+                ),
+            };
+            println!("{:<45} {:<8} {result}", validation.id, level.simd_level);
+        }
+    }
+
+    Ok(if failed { ExitCode::FAILURE } else { ExitCode::SUCCESS })
+}
@@ -0,0 +1,164 @@
+//! Deterministic instruction counts under Cachegrind, in the style of iai.
+//!
+//! Each benchmark runs twice in a child process under `valgrind --tool=cachegrind`: once for
+//! `BASELINE_ITERATIONS` and once for `BASELINE_ITERATIONS + iterations`. Subtracting the
+//! totals cancels process startup, asset loading and runner overhead, leaving the cost of
+//! `iterations` iterations. Untimed per-iteration setup (see `BenchRunner::run_batched`)
+//! cannot be separated this way and is included.
+
+use crate::error::BenchError;
+use crate::params::Params;
+use crate::registry::run_benchmark_by_id;
+use crate::result::{BenchmarkResult, CachegrindResult};
+use crate::runner::BenchRunner;
+use crate::simd::level_suffix;
+use fearless_simd::Level;
+use std::path::Path;
+use std::process::Command;
+
+/// Default number of iterations counted per benchmark.
+pub const DEFAULT_ITERATIONS: usize = 10;
+/// Iterations of the baseline run whose counts are subtracted.
+const BASELINE_ITERATIONS: usize = 1;
+
+/// Event totals from a Cachegrind output file.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CachegrindEvents {
+    /// Instructions executed (`Ir`).
+    pub instructions: u64,
+    /// L1 instruction and data misses (`I1mr + D1mr + D1mw`).
+    pub l1_misses: u64,
+    /// Last-level instruction and data misses (`ILmr + DLmr + DLmw`).
+    pub ll_misses: u64,
+}
+
+/// Parse the `events:` and `summary:` lines of a Cachegrind output file. Miss counts are
+/// zero unless Cachegrind ran with `--cache-sim=yes`.
+pub fn parse_output(contents: &str) -> Option<CachegrindEvents> {
+    let events: Vec<&str> = contents.lines().find_map(|l| l.strip_prefix("events:"))?.split_whitespace().collect();
+    let summary: Vec<u64> = contents
+        .lines()
+        .find_map(|l| l.strip_prefix("summary:"))?
+        .split_whitespace()
+        .map(str::parse)
+        .collect::<Result<_, _>>()
+        .ok()?;
+
+    let total = |names: &[&str]| -> u64 {
+        events
+            .iter()
+            .zip(&summary)
+            .filter(|(event, _)| names.contains(event))
+            .map(|(_, count)| count)
+            .sum()
+    };
+
+    if !events.contains(&"Ir") {
+        return None;
+    }
+
+    Some(CachegrindEvents {
+        instructions: total(&["Ir"]),
+        l1_misses: total(&["I1mr", "D1mr", "D1mw"]),
+        ll_misses: total(&["ILmr", "DLmr", "DLmw"]),
+    })
+}
+
+/// Run exactly `iterations` iterations of a benchmark in this process, with no warm-up,
+/// calibration or bootstrap. This is what the child process does under Cachegrind.
+pub fn run_in_process(id: &str, level: Level, iterations: usize) -> Result<BenchmarkResult, BenchError> {
+    let mut runner = BenchRunner::new(0, 0);
+    runner.warmup_ms = 0;
+    runner.fixed_iterations = Some(iterations);
+    runner.bootstrap_resamples = 0;
+    runner.timeout_ms = None;
+
+    run_benchmark_by_id(&runner, id, level, &Params::new())
+}
+
+/// Run `exe` under Cachegrind and return its event totals.
+///
+/// `exe` must accept `--run <id> --level <suffix> --iterations <n>` and call
+/// `run_in_process` with those arguments, like the `cachegrind` binary does.
+fn cachegrind_events(exe: &Path, id: &str, level: Level, iterations: usize) -> Result<CachegrindEvents, BenchError> {
+    let error = |message: String| BenchError::Cachegrind { id: id.to_string(), message };
+    let out_file = std::env::temp_dir().join(format!(
+        "vello_bench_cachegrind_{}_{iterations}.out",
+        std::process::id()
+    ));
+
+    let output = Command::new("valgrind")
+        .arg("--tool=cachegrind")
+        .arg("--cache-sim=yes")
+        .arg(format!("--cachegrind-out-file={}", out_file.display()))
+        .arg(exe)
+        .args(["--run", id, "--level", level_suffix(level), "--iterations", &iterations.to_string()])
+        .output()
+        .map_err(|e| error(format!("failed to start valgrind: {e}")))?;
+
+    if !output.status.success() {
+        let _ = std::fs::remove_file(&out_file);
+        return Err(error(format!("{}: {}", output.status, String::from_utf8_lossy(&output.stderr).trim())));
+    }
+
+    let contents = std::fs::read_to_string(&out_file).map_err(|e| error(format!("failed to read the output: {e}")));
+    let _ = std::fs::remove_file(&out_file);
+    parse_output(&contents?).ok_or_else(|| error("could not parse the output".to_string()))
+}
+
+/// Count the instructions and cache misses of `iterations` iterations of a benchmark.
+pub fn run_under_cachegrind(
+    exe: &Path,
+    id: &str,
+    level: Level,
+    iterations: usize,
+) -> Result<CachegrindResult, BenchError> {
+    let iterations = iterations.max(1);
+    let baseline = cachegrind_events(exe, id, level, BASELINE_ITERATIONS)?;
+    let measured = cachegrind_events(exe, id, level, BASELINE_ITERATIONS + iterations)?;
+    let per_iteration = |measured: u64, baseline: u64| measured.saturating_sub(baseline) as f64 / iterations as f64;
+
+    Ok(CachegrindResult {
+        id: id.to_string(),
+        simd_variant: level_suffix(level).to_string(),
+        iterations,
+        instructions: per_iteration(measured.instructions, baseline.instructions),
+        l1_misses: per_iteration(measured.l1_misses, baseline.l1_misses),
+        ll_misses: per_iteration(measured.ll_misses, baseline.ll_misses),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_output_sums_cache_misses() {
+        let contents = "\
+desc: I1 cache: 32768 B, 64 B, 8-way associative
+cmd: vello_bench_core fine/fill
+events: Ir I1mr ILmr Dr D1mr DLmr Dw D1mw DLmw
+fl=src/main.rs
+fn=main
+1 10 1 1 4 2 1 3 1 0
+summary: 1000 10 5 400 20 4 300 30 2
+";
+        let events = parse_output(contents).unwrap();
+        assert_eq!(events, CachegrindEvents { instructions: 1000, l1_misses: 60, ll_misses: 11 });
+    }
+
+    #[test]
+    fn parse_output_without_cache_simulation() {
+        let events = parse_output("events: Ir\nsummary: 123456\n").unwrap();
+        assert_eq!(events, CachegrindEvents { instructions: 123456, l1_misses: 0, ll_misses: 0 });
+    }
+
+    #[test]
+    fn parse_output_rejects_incomplete_files() {
+        assert_eq!(parse_output(""), None);
+        assert_eq!(parse_output("events: Ir\n"), None);
+        assert_eq!(parse_output("summary: 10\n"), None);
+        assert_eq!(parse_output("events: Dr Dw\nsummary: 1 2\n"), None);
+        assert_eq!(parse_output("events: Ir\nsummary: many\n"), None);
+    }
+}
@@ -0,0 +1,97 @@
+//! Argument parsing shared by the command-line tools in `src/bin`.
+//!
+//! Every tool takes `--name [value]` options and positional arguments in any order, prints
+//! its usage for `--help`, and reports errors on stderr with a failing exit code.
+
+use crate::error::BenchError;
+use crate::registry::BenchmarkInfo;
+use crate::selection::{Selection, select_benchmarks};
+use std::fmt::Display;
+use std::process::ExitCode;
+use std::str::FromStr;
+
+/// The arguments of the process, read one option at a time.
+#[derive(Debug)]
+pub struct ArgParser {
+    args: std::vec::IntoIter<String>,
+    positional: Vec<String>,
+}
+
+impl ArgParser {
+    /// Read the arguments of the current process, without the program name.
+    pub fn from_env() -> Self {
+        Self::new(std::env::args().skip(1).collect())
+    }
+
+    /// Read the given arguments.
+    pub fn new(args: Vec<String>) -> Self {
+        Self { args: args.into_iter(), positional: Vec::new() }
+    }
+
+    /// Name of the next option (e.g., "--json"), setting aside positional arguments on the way.
+    pub fn next_option(&mut self) -> Option<String> {
+        for arg in self.args.by_ref() {
+            if arg.starts_with("--") {
+                return Some(arg);
+            }
+            self.positional.push(arg);
+        }
+        None
+    }
+
+    /// Parse the value following the option `name`.
+    pub fn value<T>(&mut self, name: &str) -> Result<T, BenchError>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        let value = self.args.next().ok_or_else(|| invalid_arguments(format!("missing value for {name}")))?;
+        value.parse().map_err(|e| invalid_arguments(format!("invalid {name} {value:?}: {e}")))
+    }
+
+    /// The positional arguments, once all options have been read.
+    pub fn positional(self) -> Vec<String> {
+        self.positional
+    }
+}
+
+/// Error for an option the tool doesn't know.
+pub fn unknown_option(name: &str) -> BenchError {
+    invalid_arguments(format!("unknown option {name}"))
+}
+
+/// Error for malformed command-line arguments.
+pub fn invalid_arguments(message: String) -> BenchError {
+    BenchError::InvalidInput { what: "arguments".to_string(), message }
+}
+
+/// Parse selection terms (see `selection`); no terms select all benchmarks.
+pub fn selection(terms: &[String]) -> Result<Selection, BenchError> {
+    Selection::parse(&terms.join(" ")).map_err(invalid_selection)
+}
+
+/// Benchmarks matched by the given selection terms (all benchmarks if there are none).
+pub fn select(terms: &[String]) -> Result<Vec<BenchmarkInfo>, BenchError> {
+    select_benchmarks(&selection(terms)?).map_err(invalid_selection)
+}
+
+fn invalid_selection(message: String) -> BenchError {
+    BenchError::InvalidInput { what: "selection".to_string(), message }
+}
+
+/// Entry point of a tool: print `usage` for `--help`, otherwise run `main` and print its
+/// error, if any.
+pub fn main(usage: &str, main: impl FnOnce() -> Result<ExitCode, BenchError>) -> ExitCode {
+    if std::env::args().skip(1).any(|arg| arg == "--help" || arg == "-h") {
+        println!("Usage: {usage}");
+        return ExitCode::SUCCESS;
+    }
+
+    match main() {
+        Ok(code) => code,
+        Err(e) => {
+            eprintln!("{e}");
+            ExitCode::FAILURE
+        }
+    }
+}
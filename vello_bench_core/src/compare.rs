@@ -0,0 +1,241 @@
+//! Statistical comparison of benchmark results against a baseline.
+//!
+//! Results are matched by benchmark id, SIMD variant and parameters. When both sides have their timed
+//! samples, a Mann-Whitney U test decides whether the difference is significant; it makes
+//! no assumption about the shape of the timing distribution, which is usually skewed.
+//! Results saved before samples were recorded fall back to comparing confidence intervals,
+//! or, without those either, to a fixed threshold.
+
+use crate::params::Params;
+use crate::registry::get_benchmark_info;
+use crate::result::BenchmarkResult;
+use crate::stats;
+use serde::{Deserialize, Serialize};
+
+/// Default p-value below which a difference counts as significant.
+pub const DEFAULT_SIGNIFICANCE: f64 = 0.05;
+
+/// Default relative change of the mean below which a difference is ignored, even if it is
+/// significant.
+pub const DEFAULT_NOISE_THRESHOLD: f64 = 0.01;
+
+/// Relative change of the mean above which results without samples or confidence
+/// intervals count as changed.
+pub const FALLBACK_THRESHOLD: f64 = 0.05;
+
+/// Settings of a comparison.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CompareOptions {
+    /// P-value below which a difference counts as significant.
+    pub significance: f64,
+    /// Relative change of the mean below which a difference is reported as unchanged.
+    pub noise_threshold: f64,
+}
+
+impl Default for CompareOptions {
+    fn default() -> Self {
+        Self { significance: DEFAULT_SIGNIFICANCE, noise_threshold: DEFAULT_NOISE_THRESHOLD }
+    }
+}
+
+/// How a result compares to its baseline.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Verdict {
+    Faster,
+    Slower,
+    Unchanged,
+}
+
+/// How significance was decided.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CompareMethod {
+    /// Mann-Whitney U test on the timed samples.
+    MannWhitneyU,
+    /// Whether the confidence intervals of the means overlap.
+    IntervalOverlap,
+    /// Whether the means differ by more than `FALLBACK_THRESHOLD`.
+    Threshold,
+}
+
+/// Comparison of one benchmark result against its baseline.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Comparison {
+    /// Full benchmark ID (e.g., "fine/fill/opaque_short").
+    pub id: String,
+    /// SIMD variant of both results (e.g., "avx2").
+    pub simd_variant: String,
+    /// Mean time per iteration of the baseline, in nanoseconds.
+    pub baseline_mean_ns: f64,
+    /// Mean time per iteration of the current result, in nanoseconds.
+    pub current_mean_ns: f64,
+    /// Current mean divided by baseline mean; below 1 is faster.
+    pub ratio: f64,
+    /// Change of the mean relative to the baseline, in percent.
+    pub percent_change: f64,
+    /// P-value of the significance test, if the samples allowed one.
+    pub p_value: Option<f64>,
+    pub method: CompareMethod,
+    pub verdict: Verdict,
+}
+
+/// Why a result has no comparison.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UnmatchedReason {
+    /// The baseline has no result for the benchmark and SIMD variant.
+    NoBaseline,
+    /// The baseline ran the benchmark with different parameters.
+    ParamsDiffer,
+    /// The baseline result has no time to compare against.
+    EmptyBaseline,
+}
+
+/// A result of the current set that couldn't be compared.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Unmatched {
+    /// Full benchmark ID (e.g., "fine/fill/opaque_short").
+    pub id: String,
+    /// SIMD variant of the current result.
+    pub simd_variant: String,
+    pub reason: UnmatchedReason,
+}
+
+/// Comparison of a set of results against a baseline set.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SetComparison {
+    /// Results that have a matching baseline, in the order of the current set.
+    pub comparisons: Vec<Comparison>,
+    /// Results that don't, in the order of the current set.
+    pub unmatched: Vec<Unmatched>,
+}
+
+/// Compare `current` against `baseline`. Returns `None` if the baseline has no time to
+/// compare against.
+pub fn compare(baseline: &BenchmarkResult, current: &BenchmarkResult, options: &CompareOptions) -> Option<Comparison> {
+    let baseline_mean_ns = baseline.statistics.mean_ns;
+    let current_mean_ns = current.statistics.mean_ns;
+    if baseline_mean_ns <= 0.0 {
+        return None;
+    }
+
+    let ratio = current_mean_ns / baseline_mean_ns;
+    let relative_change = ratio - 1.0;
+    let p_value = stats::mann_whitney_u(&baseline.samples_ns, &current.samples_ns);
+    let (baseline_ci, current_ci) = (&baseline.statistics.mean_ci, &current.statistics.mean_ci);
+
+    let (method, significant) = if let Some(p) = p_value {
+        (CompareMethod::MannWhitneyU, p < options.significance)
+    } else if baseline_ci.level > 0.0 && current_ci.level > 0.0 {
+        let overlap = current_ci.lower_ns <= baseline_ci.upper_ns && baseline_ci.lower_ns <= current_ci.upper_ns;
+        (CompareMethod::IntervalOverlap, !overlap)
+    } else {
+        (CompareMethod::Threshold, relative_change.abs() > FALLBACK_THRESHOLD)
+    };
+
+    let verdict = if !significant || relative_change.abs() <= options.noise_threshold {
+        Verdict::Unchanged
+    } else if relative_change < 0.0 {
+        Verdict::Faster
+    } else {
+        Verdict::Slower
+    };
+
+    Some(Comparison {
+        id: current.id.clone(),
+        simd_variant: current.simd_variant.clone(),
+        baseline_mean_ns,
+        current_mean_ns,
+        ratio,
+        percent_change: relative_change * 100.0,
+        p_value,
+        method,
+        verdict,
+    })
+}
+
+/// Compare every result in `current` that has a baseline with the same id, SIMD variant and
+/// parameters, and list the others as unmatched.
+pub fn compare_sets(baseline: &[BenchmarkResult], current: &[BenchmarkResult], options: &CompareOptions) -> SetComparison {
+    let mut set = SetComparison::default();
+
+    for result in current {
+        let candidates: Vec<&BenchmarkResult> =
+            baseline.iter().filter(|b| b.id == result.id && b.simd_variant == result.simd_variant).collect();
+        let params = recorded_params(result);
+        let outcome = match candidates.iter().find(|b| recorded_params(b) == params) {
+            Some(base) => compare(base, result, options).ok_or(UnmatchedReason::EmptyBaseline),
+            None if candidates.is_empty() => Err(UnmatchedReason::NoBaseline),
+            None => Err(UnmatchedReason::ParamsDiffer),
+        };
+
+        match outcome {
+            Ok(comparison) => set.comparisons.push(comparison),
+            Err(reason) => {
+                set.unmatched.push(Unmatched { id: result.id.clone(), simd_variant: result.simd_variant.clone(), reason });
+            }
+        }
+    }
+
+    set
+}
+
+/// Parameters `result` ran with. Results saved before parameters were recorded ran with the
+/// defaults, which are filled in if the benchmark is still registered.
+fn recorded_params(result: &BenchmarkResult) -> Params {
+    if !result.params.is_empty() {
+        return result.params.clone();
+    }
+    get_benchmark_info(&result.id)
+        .ok()
+        .and_then(|info| Params::resolve(&info.params, &Params::new()).ok())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::params::ParamValue;
+
+    fn result(simd_variant: &str, width: Option<i64>) -> BenchmarkResult {
+        let mut result: BenchmarkResult = serde_json::from_value(serde_json::json!({
+            "id": "test/fill",
+            "category": "test",
+            "name": "fill",
+            "simd_variant": simd_variant,
+            "statistics": { "mean_ns": 100.0 },
+            "timestamp_ms": 0
+        }))
+        .unwrap();
+        if let Some(width) = width {
+            result.params = Params::new().with("width", ParamValue::Int(width));
+        }
+        result
+    }
+
+    #[test]
+    fn compare_sets_matches_variant_and_params() {
+        let baseline = [result("scalar", Some(32)), result("avx2", Some(32))];
+        let current = [result("scalar", Some(32)), result("avx2", Some(64)), result("neon", Some(32))];
+        let set = compare_sets(&baseline, &current, &CompareOptions::default());
+
+        assert_eq!(set.comparisons.len(), 1);
+        assert_eq!(set.comparisons[0].simd_variant, "scalar");
+        assert_eq!(set.comparisons[0].verdict, Verdict::Unchanged);
+
+        let unmatched: Vec<_> = set.unmatched.iter().map(|u| (u.simd_variant.as_str(), u.reason)).collect();
+        assert_eq!(unmatched, [("avx2", UnmatchedReason::ParamsDiffer), ("neon", UnmatchedReason::NoBaseline)]);
+    }
+
+    #[test]
+    fn compare_sets_reports_empty_baselines() {
+        let mut baseline = result("scalar", None);
+        baseline.statistics.mean_ns = 0.0;
+        let set = compare_sets(&[baseline], &[result("scalar", None)], &CompareOptions::default());
+
+        assert!(set.comparisons.is_empty());
+        assert_eq!(set.unmatched[0].reason, UnmatchedReason::EmptyBaseline);
+    }
+}
@@ -1,3 +1,4 @@
+use crate::error::BenchError;
 use std::sync::OnceLock;
 use usvg::tiny_skia_path::PathSegment;
 use usvg::{Group, Node};
@@ -9,14 +10,27 @@ use vello_common::strip::Strip;
 use vello_common::tile::Tiles;
 use vello_common::{flatten, strip};
 
-static DATA: OnceLock<Vec<DataItem>> = OnceLock::new();
+static DATA: OnceLock<Vec<Result<DataItem, BenchError>>> = OnceLock::new();
 
 const TIGER_SVG: &[u8] = include_bytes!("../assets/Ghostscript_Tiger.svg");
 
-pub fn get_data_items() -> &'static [DataItem] {
-    DATA.get_or_init(|| {
-        vec![DataItem::from_svg_data("Ghostscript_Tiger", TIGER_SVG)]
-    })
+/// The SVG files the data-driven benchmarks run on, by name.
+const SVGS: &[(&str, &[u8])] = &[("Ghostscript_Tiger", TIGER_SVG)];
+
+/// Names of all data items, without parsing them.
+pub fn data_item_names() -> impl Iterator<Item = &'static str> {
+    SVGS.iter().map(|(name, _)| *name)
+}
+
+/// The data item with the given name, parsed on first use. Fails if there is no such item
+/// or its SVG couldn't be parsed.
+pub fn get_data_item(name: &str) -> Result<&'static DataItem, BenchError> {
+    let items = DATA.get_or_init(|| SVGS.iter().map(|(name, data)| DataItem::from_svg_data(name, data)).collect());
+    let index = SVGS.iter().position(|(n, _)| *n == name).ok_or_else(|| BenchError::Asset {
+        name: name.to_string(),
+        message: "no such data item".to_string(),
+    })?;
+    items[index].as_ref().map_err(Clone::clone)
 }
 
 #[derive(Clone, Debug)]
@@ -30,12 +44,13 @@ pub struct DataItem {
 
 impl DataItem {
     /// Create a DataItem from SVG data bytes.
-    pub fn from_svg_data(name: &str, data: &[u8]) -> Self {
-        let tree = usvg::Tree::from_data(data, &usvg::Options::default()).unwrap();
+    pub fn from_svg_data(name: &str, data: &[u8]) -> Result<Self, BenchError> {
+        let tree = usvg::Tree::from_data(data, &usvg::Options::default())
+            .map_err(|e| BenchError::Asset { name: name.to_string(), message: e.to_string() })?;
         let mut ctx = ConversionContext::new();
         convert(&mut ctx, tree.root());
 
-        Self {
+        Ok(Self {
             name: name.to_string(),
             fills: ctx.fills,
             strokes: ctx.strokes,
@@ -49,7 +64,7 @@ impl DataItem {
                 reason = "It's okay to ignore for benchmarking."
             )]
             height: tree.size().height() as u16,
-        }
+        })
     }
 
     /// Get the raw flattened lines of both fills and strokes.
@@ -0,0 +1,154 @@
+//! Description of the machine, build and settings a run was measured with.
+
+use crate::preflight::{self, PreflightWarning};
+use crate::runner::{BenchRunner, RunnerSettings};
+use serde::{Deserialize, Serialize};
+
+/// Where and how a set of results was produced.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RunEnvironment {
+    /// CPU model name, if the OS reports one.
+    pub cpu_model: Option<String>,
+    /// Number of logical cores available to the process.
+    pub logical_cores: usize,
+    /// Operating system (e.g., "linux", "macos"), or "browser" for WASM.
+    pub os: String,
+    /// Kernel release, if known.
+    pub kernel: Option<String>,
+    /// CPU architecture the benchmarks were compiled for (e.g., "x86_64", "wasm32").
+    pub arch: String,
+    /// Browser user agent (WASM only).
+    pub user_agent: Option<String>,
+    /// Output of `rustc --version` at build time.
+    pub rustc_version: Option<String>,
+    /// Cargo profile the benchmarks were built with (e.g., "release").
+    pub build_profile: String,
+    /// Target features enabled at compile time.
+    pub target_features: Vec<String>,
+    /// Whether the WASM `simd128` target feature was enabled.
+    pub simd128: bool,
+    /// Whether the counting allocator was compiled in (see `alloc`).
+    pub alloc_tracking: bool,
+    /// Git revision of the vello checkout the benchmarks were built against, if found.
+    pub vello_revision: Option<String>,
+    /// Settings of the runner.
+    pub runner: RunnerSettings,
+    /// Noise sources found by the preflight checks, if they were run (see `with_preflight`).
+    pub noise_warnings: Vec<PreflightWarning>,
+}
+
+impl RunEnvironment {
+    /// Describe the current machine and build, and the settings of `runner`. The preflight
+    /// checks are not run.
+    pub fn capture(runner: &BenchRunner) -> Self {
+        let target_features = option_env!("VELLO_BENCH_TARGET_FEATURES")
+            .unwrap_or_default()
+            .split(',')
+            .filter(|f| !f.is_empty())
+            .map(str::to_string)
+            .collect();
+
+        Self {
+            cpu_model: cpu_model(),
+            logical_cores: logical_cores(),
+            os: if cfg!(target_arch = "wasm32") { "browser".to_string() } else { std::env::consts::OS.to_string() },
+            kernel: kernel(),
+            arch: std::env::consts::ARCH.to_string(),
+            user_agent: user_agent(),
+            rustc_version: option_env!("VELLO_BENCH_RUSTC_VERSION").map(str::to_string),
+            build_profile: option_env!("VELLO_BENCH_PROFILE").unwrap_or_default().to_string(),
+            target_features,
+            simd128: cfg!(target_feature = "simd128"),
+            alloc_tracking: crate::alloc::is_enabled(),
+            vello_revision: option_env!("VELLO_BENCH_VELLO_REVISION").map(str::to_string),
+            runner: runner.settings(),
+            noise_warnings: Vec::new(),
+        }
+    }
+
+    /// Run the preflight checks and record the noise sources they find.
+    pub fn with_preflight(mut self) -> Self {
+        self.noise_warnings = preflight::run_checks().warnings;
+        self
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn cpu_model() -> Option<String> {
+    let cpuinfo = std::fs::read_to_string("/proc/cpuinfo").ok()?;
+    // x86 reports "model name"; many ARM kernels only report "Hardware" or "Model".
+    ["model name", "Hardware", "Model"].iter().find_map(|key| {
+        cpuinfo.lines().find_map(|line| {
+            let (name, value) = line.split_once(':')?;
+            (name.trim() == *key && !value.trim().is_empty()).then(|| value.trim().to_string())
+        })
+    })
+}
+
+#[cfg(target_os = "macos")]
+fn cpu_model() -> Option<String> {
+    command_output("sysctl", &["-n", "machdep.cpu.brand_string"])
+}
+
+#[cfg(target_os = "windows")]
+fn cpu_model() -> Option<String> {
+    std::env::var("PROCESSOR_IDENTIFIER").ok()
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+fn cpu_model() -> Option<String> {
+    None
+}
+
+#[cfg(target_os = "linux")]
+fn kernel() -> Option<String> {
+    std::fs::read_to_string("/proc/sys/kernel/osrelease").ok().map(|s| s.trim().to_string())
+}
+
+#[cfg(all(unix, not(target_os = "linux")))]
+fn kernel() -> Option<String> {
+    command_output("uname", &["-r"])
+}
+
+#[cfg(not(unix))]
+fn kernel() -> Option<String> {
+    None
+}
+
+#[cfg(all(unix, not(target_os = "linux")))]
+fn command_output(program: &str, args: &[&str]) -> Option<String> {
+    let output = std::process::Command::new(program).args(args).output().ok()?;
+    output.status.success().then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn logical_cores() -> usize {
+    std::thread::available_parallelism().map_or(1, |n| n.get())
+}
+
+#[cfg(target_arch = "wasm32")]
+fn logical_cores() -> usize {
+    navigator_property("hardwareConcurrency")
+        .and_then(|v| v.as_f64())
+        .map_or(1, |n| n as usize)
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn user_agent() -> Option<String> {
+    None
+}
+
+#[cfg(target_arch = "wasm32")]
+fn user_agent() -> Option<String> {
+    navigator_property("userAgent").and_then(|v| v.as_string())
+}
+
+/// Read a property of `navigator`, which exists in both Window and Worker contexts.
+#[cfg(target_arch = "wasm32")]
+fn navigator_property(name: &str) -> Option<wasm_bindgen::JsValue> {
+    use wasm_bindgen::JsValue;
+
+    let navigator = js_sys::Reflect::get(&js_sys::global(), &JsValue::from_str("navigator")).ok()?;
+    js_sys::Reflect::get(&navigator, &JsValue::from_str(name)).ok()
+}
@@ -0,0 +1,43 @@
+//! Why a benchmark couldn't be run.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Error running a benchmark.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum BenchError {
+    /// No benchmark is registered with this ID.
+    UnknownBenchmark { id: String },
+    /// The SIMD level is unknown or not supported by this CPU.
+    UnsupportedLevel { level: String },
+    /// A parameter override was rejected (see `Params::resolve`).
+    InvalidParams { id: String, message: String },
+    /// An argument from a frontend, such as runner options or a result set, is malformed.
+    InvalidInput { what: String, message: String },
+    /// An embedded asset, such as an SVG or PNG file, couldn't be decoded.
+    Asset { name: String, message: String },
+    /// The benchmark panicked while running on its own thread (see `interleave`).
+    Panicked { id: String, message: String },
+    /// Running a benchmark under Cachegrind failed (see `cachegrind`).
+    Cachegrind { id: String, message: String },
+    /// Strict mode is on and the preflight checks found noise sources (see `preflight`).
+    NoisySystem { warnings: Vec<String> },
+}
+
+impl fmt::Display for BenchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownBenchmark { id } => write!(f, "unknown benchmark {id:?}"),
+            Self::UnsupportedLevel { level } => write!(f, "SIMD level {level:?} is not supported on this CPU"),
+            Self::InvalidParams { id, message } => write!(f, "invalid parameters for {id}: {message}"),
+            Self::InvalidInput { what, message } => write!(f, "invalid {what}: {message}"),
+            Self::Asset { name, message } => write!(f, "failed to load asset {name:?}: {message}"),
+            Self::Panicked { id, message } => write!(f, "{id} panicked: {message}"),
+            Self::Cachegrind { id, message } => write!(f, "cachegrind run of {id} failed: {message}"),
+            Self::NoisySystem { warnings } => write!(f, "refusing to run on a noisy system: {}", warnings.join("; ")),
+        }
+    }
+}
+
+impl std::error::Error for BenchError {}
@@ -0,0 +1,207 @@
+//! Interleaved measurement of several benchmark variants.
+//!
+//! Running variants one after another lets thermal and frequency drift bias the comparison.
+//! Here, each variant runs on its own thread, but the threads take turns: only the one
+//! holding the baton executes, and it passes the baton on after every measurement sample.
+//! The variants first take turns to set up, warm up and calibrate, and only once all have
+//! done so do their timed samples alternate, so that no sample is separated from the
+//! previous variant's by a calibration. Sample `i` of each variant then forms one pair of a
+//! paired comparison against the first variant.
+
+use crate::error::BenchError;
+use crate::params::Params;
+use crate::registry::run_benchmark_by_id;
+use crate::result::{BenchmarkResult, ConfidenceInterval};
+use crate::runner::BenchRunner;
+use crate::simd::level_from_suffix;
+use crate::stats;
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Condvar, Mutex};
+
+/// A benchmark configuration taking part in an interleaved run.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Variant {
+    /// Full benchmark ID (e.g., "fine/fill/opaque_short").
+    pub id: String,
+    /// SIMD level suffix (e.g., "avx2", "scalar").
+    pub simd_level: String,
+    /// Parameter overrides.
+    #[serde(default)]
+    pub params: Params,
+}
+
+/// Paired comparison of one variant against the first (the baseline).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PairedDifference {
+    /// Index of the variant in `InterleavedResult::results`.
+    pub variant: usize,
+    /// Number of sample pairs.
+    pub pairs: usize,
+    /// Mean of `variant - baseline` over the pairs, in nanoseconds per iteration.
+    pub mean_diff_ns: f64,
+    /// Bootstrap confidence interval of the mean difference.
+    pub mean_diff_ci: ConfidenceInterval,
+    /// Mean difference relative to the mean of the baseline's paired samples.
+    pub relative_diff: f64,
+}
+
+/// Results of an interleaved run.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InterleavedResult {
+    /// One result per variant, in the order they were given.
+    pub results: Vec<BenchmarkResult>,
+    /// Paired differences of every variant but the first against the first.
+    pub differences: Vec<PairedDifference>,
+}
+
+/// Turn-taking between the threads of an interleaved run.
+#[derive(Debug)]
+struct Baton {
+    state: Mutex<BatonState>,
+    turn_changed: Condvar,
+}
+
+#[derive(Debug)]
+struct BatonState {
+    holder: usize,
+    finished: Vec<bool>,
+}
+
+impl Baton {
+    fn new(participants: usize) -> Self {
+        Self {
+            state: Mutex::new(BatonState { holder: 0, finished: vec![false; participants] }),
+            turn_changed: Condvar::new(),
+        }
+    }
+
+    fn wait_for_turn(&self, index: usize) {
+        let mut state = self.state.lock().unwrap();
+        while state.holder != index {
+            state = self.turn_changed.wait(state).unwrap();
+        }
+    }
+
+    /// Hand the baton to the next participant that hasn't finished, if any.
+    fn pass(&self, index: usize, finished: bool) {
+        let mut state = self.state.lock().unwrap();
+        state.finished[index] |= finished;
+        let n = state.finished.len();
+        if let Some(next) = (1..=n).map(|i| (index + i) % n).find(|&i| !state.finished[i]) {
+            state.holder = next;
+        }
+        self.turn_changed.notify_all();
+    }
+}
+
+/// A participant's handle on the baton, given to its runner.
+#[derive(Debug, Clone)]
+pub(crate) struct Turn {
+    baton: Arc<Baton>,
+    index: usize,
+}
+
+impl Turn {
+    /// Let the other participants take a sample, and wait until it's our turn again.
+    pub(crate) fn yield_turn(&self) {
+        self.baton.pass(self.index, false);
+        self.baton.wait_for_turn(self.index);
+    }
+}
+
+/// Marks the participant as finished when dropped, even if its benchmark panicked, so the
+/// others don't wait forever.
+struct FinishOnDrop<'a>(&'a Turn);
+
+impl Drop for FinishOnDrop<'_> {
+    fn drop(&mut self) {
+        self.0.baton.pass(self.0.index, true);
+    }
+}
+
+/// Run `variants` with interleaved measurement samples, using the settings and timer of
+/// `runner`.
+/// Native only, since it needs threads.
+///
+/// The target precision is ignored so that all variants take the same number of samples,
+/// and the timeout is scaled by the number of variants, since each one waits while the
+/// others sample. Fails, before running anything, if a variant has a SIMD level that isn't
+/// available or strict mode finds a noisy system, or with the first error of any variant.
+pub fn run_interleaved(runner: &BenchRunner, variants: &[Variant]) -> Result<InterleavedResult, BenchError> {
+    let levels = variants.iter().map(|v| level_from_suffix(&v.simd_level)).collect::<Result<Vec<_>, _>>()?;
+    runner.check_preflight()?;
+
+    let mut settings = runner.settings();
+    settings.target_precision = None;
+    settings.timeout_ms = settings.timeout_ms.map(|t| t.saturating_mul(variants.len() as u64));
+    let baton = Arc::new(Baton::new(variants.len()));
+
+    let results: Vec<Result<BenchmarkResult, BenchError>> = std::thread::scope(|scope| {
+        let handles: Vec<_> = variants
+            .iter()
+            .zip(levels)
+            .enumerate()
+            .map(|(index, (variant, level))| {
+                let settings = &settings;
+                let timer = runner.timer.clone();
+                let turn = Turn { baton: baton.clone(), index };
+                scope.spawn(move || {
+                    turn.baton.wait_for_turn(index);
+                    let _finish = FinishOnDrop(&turn);
+                    let mut runner = BenchRunner::from_settings(settings);
+                    runner.timer = timer;
+                    runner.turn = Some(turn.clone());
+                    run_benchmark_by_id(&runner, &variant.id, level, &variant.params)
+                })
+            })
+            .collect();
+
+        handles
+            .into_iter()
+            .zip(variants)
+            .map(|(handle, variant)| {
+                handle.join().unwrap_or_else(|payload| {
+                    Err(BenchError::Panicked { id: variant.id.clone(), message: panic_message(payload.as_ref()) })
+                })
+            })
+            .collect()
+    });
+
+    let results: Vec<BenchmarkResult> = results.into_iter().collect::<Result<_, _>>()?;
+    let differences = (1..results.len())
+        .map(|variant| paired_difference(&results[0], &results[variant], variant, runner))
+        .collect();
+
+    Ok(InterleavedResult { results, differences })
+}
+
+/// The message of a panic, if it was given one.
+fn panic_message(payload: &(dyn std::any::Any + Send)) -> String {
+    payload
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "unknown panic".to_string())
+}
+
+fn paired_difference(
+    baseline: &BenchmarkResult,
+    other: &BenchmarkResult,
+    variant: usize,
+    runner: &BenchRunner,
+) -> PairedDifference {
+    let pairs = baseline.samples_ns.len().min(other.samples_ns.len());
+    let diffs: Vec<f64> = (0..pairs).map(|i| other.samples_ns[i] - baseline.samples_ns[i]).collect();
+    let mean_diff_ns = stats::mean(&diffs);
+    let baseline_mean = stats::mean(&baseline.samples_ns[..pairs]);
+
+    PairedDifference {
+        variant,
+        pairs,
+        mean_diff_ns,
+        mean_diff_ci: ConfidenceInterval::bootstrap(&diffs, runner.confidence_level, runner.bootstrap_resamples, |s| {
+            stats::mean(s)
+        }),
+        relative_diff: if baseline_mean == 0.0 { 0.0 } else { mean_diff_ns / baseline_mean },
+    }
+}
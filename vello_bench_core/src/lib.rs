@@ -1,12 +1,45 @@
+pub mod affinity;
+pub mod alloc;
 pub mod benchmarks;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod cachegrind;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod cli;
+pub mod compare;
 pub mod data;
+pub mod environment;
+pub mod error;
+pub mod interleave;
+pub mod matrix;
+pub mod params;
+pub mod perf;
+pub mod preflight;
 pub mod registry;
 pub mod result;
 pub mod runner;
+pub mod selection;
 pub mod simd;
+pub mod stats;
+pub mod timer;
+pub mod validate;
 
 
-pub use registry::{get_benchmark_list, run_benchmark_by_id, BenchmarkInfo};
-pub use result::{BenchmarkResult, Statistics};
-pub use runner::BenchRunner;
+pub use compare::{CompareOptions, Comparison, SetComparison, Unmatched, UnmatchedReason, Verdict, compare_sets};
+pub use environment::RunEnvironment;
+pub use error::BenchError;
+pub use interleave::{InterleavedResult, PairedDifference, Variant, run_interleaved};
+pub use matrix::{LevelMatrix, MatrixCell, MatrixRow, run_level_matrix};
+pub use params::{ParamInfo, ParamKind, ParamValue, Params};
+pub use preflight::{PreflightReport, PreflightWarning};
+pub use registry::{
+    Benchmark, BenchmarkInfo, Registry, get_benchmark_info, get_benchmark_list, register, run_benchmark_by_id,
+};
+pub use result::{
+    AllocationStats, BenchmarkResult, CachegrindResult, CalibrationBatch, ConfidenceInterval, CounterStats, Outliers,
+    ResultSet, RunDiagnostics, Statistics, StopReason, Throughput, ThroughputUnit,
+};
+pub use runner::{BenchRunner, RunnerOptions, RunnerSettings};
+pub use selection::{Selection, select_benchmarks};
 pub use simd::{SimdLevelInfo, available_level_infos, available_levels, level_from_suffix, level_suffix};
+pub use timer::{Timer, TimerInfo, available_timer_infos};
+pub use validate::{LevelValidation, Mismatch, Validation, validate_benchmark};
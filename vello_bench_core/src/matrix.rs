@@ -0,0 +1,113 @@
+//! Running benchmarks at every SIMD level, for a table of SIMD speedups.
+
+use crate::error::BenchError;
+use crate::params::Params;
+use crate::registry::run_benchmark_by_id;
+use crate::result::BenchmarkResult;
+use crate::runner::BenchRunner;
+use crate::selection::{Selection, select_benchmarks};
+use crate::simd::{available_levels, level_suffix};
+use serde::{Deserialize, Serialize};
+
+/// Results of benchmarks at every available SIMD level.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LevelMatrix {
+    /// SIMD level suffixes of the columns, from best to worst (e.g., "avx2", ..., "scalar").
+    pub levels: Vec<String>,
+    /// One row per benchmark.
+    pub rows: Vec<MatrixRow>,
+}
+
+/// Results of one benchmark at every level.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MatrixRow {
+    /// Full benchmark ID (e.g., "fine/fill/opaque_short").
+    pub id: String,
+    /// Level with the lowest mean, if any run succeeded.
+    pub best_level: Option<String>,
+    /// Whether the benchmark ignores the SIMD level, in which case it only ran at scalar.
+    #[serde(default)]
+    pub level_agnostic: bool,
+    /// One cell per level, in the order of `LevelMatrix::levels`.
+    pub cells: Vec<MatrixCell>,
+}
+
+/// Result of one benchmark at one level.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MatrixCell {
+    /// SIMD level suffix (e.g., "avx2").
+    pub simd_level: String,
+    /// `None` if the run failed, or was skipped because the benchmark is level-agnostic.
+    pub result: Option<BenchmarkResult>,
+    /// Why the run failed, if it did.
+    pub error: Option<String>,
+    /// Mean of scalar divided by the mean of this level; above 1 is faster than scalar.
+    pub speedup_vs_scalar: Option<f64>,
+    /// Lowest mean of the row divided by the mean of this level; 1 for the best level.
+    pub speedup_vs_best: Option<f64>,
+}
+
+/// Run each selected benchmark at every available SIMD level, with the same parameter
+/// overrides. Level-agnostic benchmarks only run at scalar, as the other levels would run
+/// the same code. A failed run leaves an error in its cell rather than stopping the matrix;
+/// only an invalid selection or, in strict mode, a noisy system fails as a whole.
+pub fn run_level_matrix(
+    runner: &BenchRunner,
+    selection: &Selection,
+    overrides: &Params,
+) -> Result<LevelMatrix, BenchError> {
+    let benchmarks = select_benchmarks(selection)
+        .map_err(|message| BenchError::InvalidInput { what: "selection".to_string(), message })?;
+    runner.check_preflight()?;
+    let levels = available_levels();
+    let rows = benchmarks
+        .iter()
+        .map(|info| {
+            let (id, level_agnostic) = (&info.id, info.level_agnostic);
+            let cells = levels
+                .iter()
+                .map(|level| {
+                    let (result, error) = if level_agnostic && level_suffix(*level) != "scalar" {
+                        (None, None)
+                    } else {
+                        match run_benchmark_by_id(runner, id, *level, overrides) {
+                            Ok(result) => (Some(result), None),
+                            Err(e) => (None, Some(e.to_string())),
+                        }
+                    };
+                    MatrixCell {
+                        simd_level: level_suffix(*level).to_string(),
+                        result,
+                        error,
+                        speedup_vs_scalar: None,
+                        speedup_vs_best: None,
+                    }
+                })
+                .collect();
+            MatrixRow::new(id, level_agnostic, cells)
+        })
+        .collect();
+
+    Ok(LevelMatrix { levels: levels.into_iter().map(|l| level_suffix(l).to_string()).collect(), rows })
+}
+
+impl MatrixRow {
+    /// A row of `cells`, with the best level and the speedups filled in.
+    fn new(id: &str, level_agnostic: bool, mut cells: Vec<MatrixCell>) -> Self {
+        let mean = |cell: &MatrixCell| cell.result.as_ref().map(|r| r.statistics.mean_ns).filter(|m| *m > 0.0);
+        let scalar_mean = cells.iter().find(|c| c.simd_level == "scalar").and_then(mean);
+        let best = cells
+            .iter()
+            .filter_map(|c| Some((c.simd_level.clone(), mean(c)?)))
+            .min_by(|(_, a), (_, b)| a.total_cmp(b));
+
+        for cell in &mut cells {
+            if let Some(m) = mean(cell) {
+                cell.speedup_vs_scalar = scalar_mean.map(|s| s / m);
+                cell.speedup_vs_best = best.as_ref().map(|(_, b)| b / m);
+            }
+        }
+
+        Self { id: id.to_string(), best_level: best.map(|(level, _)| level), level_agnostic, cells }
+    }
+}
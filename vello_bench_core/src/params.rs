@@ -0,0 +1,244 @@
+//! Runtime parameters of benchmarks.
+//!
+//! A benchmark declares its parameters with defaults and allowed values in its
+//! `BenchmarkInfo`. Callers may override any of them when running it, for example to sweep
+//! fill widths without recompiling.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Description of a parameter.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ParamInfo {
+    pub name: String,
+    pub description: String,
+    /// Type, default and allowed values.
+    #[serde(flatten)]
+    pub kind: ParamKind,
+}
+
+/// Type, default and allowed values of a parameter.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ParamKind {
+    /// An integer in `min..=max`.
+    Int { default: i64, min: i64, max: i64 },
+    /// A number in `min..=max`.
+    Float { default: f64, min: f64, max: f64 },
+    /// One of `options`.
+    Choice { default: String, options: Vec<String> },
+}
+
+impl ParamInfo {
+    /// An integer parameter in `min..=max`.
+    pub fn int(name: &str, description: &str, default: i64, min: i64, max: i64) -> Self {
+        Self { name: name.into(), description: description.into(), kind: ParamKind::Int { default, min, max } }
+    }
+
+    /// A numeric parameter in `min..=max`.
+    pub fn float(name: &str, description: &str, default: f64, min: f64, max: f64) -> Self {
+        Self { name: name.into(), description: description.into(), kind: ParamKind::Float { default, min, max } }
+    }
+
+    /// A parameter taking one of `options`.
+    pub fn choice(name: &str, description: &str, default: &str, options: &[&str]) -> Self {
+        Self {
+            name: name.into(),
+            description: description.into(),
+            kind: ParamKind::Choice {
+                default: default.into(),
+                options: options.iter().map(|o| (*o).into()).collect(),
+            },
+        }
+    }
+
+    fn default_value(&self) -> ParamValue {
+        match &self.kind {
+            ParamKind::Int { default, .. } => ParamValue::Int(*default),
+            ParamKind::Float { default, .. } => ParamValue::Float(*default),
+            ParamKind::Choice { default, .. } => ParamValue::Choice(default.clone()),
+        }
+    }
+
+    /// Check `value` against the type and allowed values, converting integers given for
+    /// numeric parameters.
+    fn check(&self, value: &ParamValue) -> Result<ParamValue, String> {
+        let name = &self.name;
+        match (&self.kind, value) {
+            (ParamKind::Int { min, max, .. }, ParamValue::Int(v)) => {
+                if (*min..=*max).contains(v) {
+                    Ok(ParamValue::Int(*v))
+                } else {
+                    Err(format!("parameter {name:?} must be in {min}..={max}, got {v}"))
+                }
+            }
+            (ParamKind::Float { min, max, .. }, ParamValue::Int(_) | ParamValue::Float(_)) => {
+                let v = value.as_f64().unwrap_or_default();
+                if (*min..=*max).contains(&v) {
+                    Ok(ParamValue::Float(v))
+                } else {
+                    Err(format!("parameter {name:?} must be in {min}..={max}, got {v}"))
+                }
+            }
+            (ParamKind::Choice { options, .. }, ParamValue::Choice(v)) => {
+                if options.contains(v) {
+                    Ok(ParamValue::Choice(v.clone()))
+                } else {
+                    Err(format!("parameter {name:?} must be one of {}, got {v:?}", options.join(", ")))
+                }
+            }
+            (kind, value) => Err(format!("parameter {name:?} expects {}, got {value:?}", kind.type_name())),
+        }
+    }
+}
+
+impl ParamKind {
+    fn type_name(&self) -> &'static str {
+        match self {
+            Self::Int { .. } => "an integer",
+            Self::Float { .. } => "a number",
+            Self::Choice { .. } => "a string",
+        }
+    }
+}
+
+/// Value of a parameter.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ParamValue {
+    Int(i64),
+    Float(f64),
+    Choice(String),
+}
+
+impl ParamValue {
+    fn as_f64(&self) -> Option<f64> {
+        match self {
+            Self::Int(v) => Some(*v as f64),
+            Self::Float(v) => Some(*v),
+            Self::Choice(_) => None,
+        }
+    }
+}
+
+/// Parameter values by name.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Params(pub BTreeMap<String, ParamValue>);
+
+impl Params {
+    /// No overrides.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add or replace a value.
+    pub fn with(mut self, name: &str, value: ParamValue) -> Self {
+        self.0.insert(name.into(), value);
+        self
+    }
+
+    /// Whether no values are set.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Fill in the defaults of `params` for everything not overridden, after checking the
+    /// overrides against their declarations.
+    pub fn resolve(params: &[ParamInfo], overrides: &Self) -> Result<Self, String> {
+        if let Some(unknown) = overrides.0.keys().find(|name| !params.iter().any(|p| &p.name == *name)) {
+            return Err(format!("unknown parameter {unknown:?}"));
+        }
+
+        let mut resolved = Self::new();
+        for param in params {
+            let value = match overrides.0.get(&param.name) {
+                Some(value) => param.check(value)?,
+                None => param.default_value(),
+            };
+            resolved.0.insert(param.name.clone(), value);
+        }
+        Ok(resolved)
+    }
+
+    /// Value of an integer parameter. Panics if the benchmark didn't declare it.
+    pub fn int(&self, name: &str) -> i64 {
+        match self.0.get(name) {
+            Some(ParamValue::Int(v)) => *v,
+            _ => panic!("no integer parameter {name:?}"),
+        }
+    }
+
+    /// Value of a numeric parameter. Panics if the benchmark didn't declare it.
+    pub fn float(&self, name: &str) -> f64 {
+        match self.0.get(name) {
+            Some(ParamValue::Float(v)) => *v,
+            _ => panic!("no numeric parameter {name:?}"),
+        }
+    }
+
+    /// Value of a choice parameter. Panics if the benchmark didn't declare it.
+    pub fn choice(&self, name: &str) -> &str {
+        match self.0.get(name) {
+            Some(ParamValue::Choice(v)) => v,
+            _ => panic!("no choice parameter {name:?}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn declared() -> Vec<ParamInfo> {
+        vec![
+            ParamInfo::int("width", "Width", 16, 1, 256),
+            ParamInfo::float("alpha", "Alpha", 0.5, 0.0, 1.0),
+            ParamInfo::choice("fill", "Fill rule", "non_zero", &["non_zero", "even_odd"]),
+        ]
+    }
+
+    #[test]
+    fn resolve_fills_in_defaults() {
+        let resolved = Params::resolve(&declared(), &Params::new()).unwrap();
+        assert_eq!(resolved.int("width"), 16);
+        assert_eq!(resolved.float("alpha"), 0.5);
+        assert_eq!(resolved.choice("fill"), "non_zero");
+    }
+
+    #[test]
+    fn resolve_applies_overrides() {
+        let overrides =
+            Params::new().with("width", ParamValue::Int(64)).with("fill", ParamValue::Choice("even_odd".into()));
+        let resolved = Params::resolve(&declared(), &overrides).unwrap();
+        assert_eq!(resolved.int("width"), 64);
+        assert_eq!(resolved.float("alpha"), 0.5);
+        assert_eq!(resolved.choice("fill"), "even_odd");
+    }
+
+    #[test]
+    fn resolve_converts_integers_for_numeric_parameters() {
+        let overrides = Params::new().with("alpha", ParamValue::Int(1));
+        let resolved = Params::resolve(&declared(), &overrides).unwrap();
+        assert_eq!(resolved.0["alpha"], ParamValue::Float(1.0));
+    }
+
+    #[test]
+    fn resolve_rejects_invalid_overrides() {
+        let reject = |name: &str, value: ParamValue| Params::resolve(&declared(), &Params::new().with(name, value));
+        assert_eq!(reject("height", ParamValue::Int(1)), Err("unknown parameter \"height\"".into()));
+        assert_eq!(reject("width", ParamValue::Int(0)), Err("parameter \"width\" must be in 1..=256, got 0".into()));
+        assert_eq!(
+            reject("alpha", ParamValue::Float(1.5)),
+            Err("parameter \"alpha\" must be in 0..=1, got 1.5".into())
+        );
+        assert_eq!(
+            reject("fill", ParamValue::Choice("odd".into())),
+            Err("parameter \"fill\" must be one of non_zero, even_odd, got \"odd\"".into())
+        );
+        assert_eq!(
+            reject("width", ParamValue::Float(2.5)),
+            Err("parameter \"width\" expects an integer, got Float(2.5)".into())
+        );
+    }
+}
@@ -0,0 +1,243 @@
+//! Hardware and software performance counters via Linux `perf_event_open`.
+//!
+//! Counters measure the calling thread only. The runner opens them at the start of the
+//! measurement phase and enables them around the timed part of each batch, like allocation
+//! tracking. Counters the kernel refuses to open (because of `perf_event_paranoid`, a
+//! container seccomp profile, or missing hardware support) are left out; on other platforms
+//! nothing is ever opened.
+
+/// Raw counter totals. `None` means the counter could not be opened.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct PerfCounts {
+    pub instructions: Option<u64>,
+    pub cycles: Option<u64>,
+    pub branch_misses: Option<u64>,
+    pub cache_misses: Option<u64>,
+    pub task_clock_ns: Option<u64>,
+    pub context_switches: Option<u64>,
+    /// Whether any counter was multiplexed with others, so its total is extrapolated.
+    pub multiplexed: bool,
+}
+
+/// Open the counters on the current thread, disabled. Returns whether any could be opened.
+pub fn open() -> bool {
+    #[cfg(target_os = "linux")]
+    {
+        linux::open()
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        false
+    }
+}
+
+/// Enable or disable the counters opened on the current thread, if any.
+pub fn set_counting(enabled: bool) {
+    #[cfg(target_os = "linux")]
+    linux::set_counting(enabled);
+    #[cfg(not(target_os = "linux"))]
+    let _ = enabled;
+}
+
+/// Read and close the counters of the current thread, or `None` if none were open.
+pub fn close() -> Option<PerfCounts> {
+    #[cfg(target_os = "linux")]
+    {
+        linux::close()
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        None
+    }
+}
+
+#[cfg(target_os = "linux")]
+#[allow(unsafe_code)]
+mod linux {
+    use super::PerfCounts;
+    use std::cell::RefCell;
+    use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+
+    const PERF_TYPE_HARDWARE: u32 = 0;
+    const PERF_TYPE_SOFTWARE: u32 = 1;
+
+    const PERF_COUNT_HW_CPU_CYCLES: u64 = 0;
+    const PERF_COUNT_HW_INSTRUCTIONS: u64 = 1;
+    const PERF_COUNT_HW_CACHE_MISSES: u64 = 3;
+    const PERF_COUNT_HW_BRANCH_MISSES: u64 = 5;
+    const PERF_COUNT_SW_TASK_CLOCK: u64 = 1;
+    const PERF_COUNT_SW_CONTEXT_SWITCHES: u64 = 3;
+
+    const PERF_FORMAT_TOTAL_TIME_ENABLED: u64 = 1 << 0;
+    const PERF_FORMAT_TOTAL_TIME_RUNNING: u64 = 1 << 1;
+
+    const FLAG_DISABLED: u64 = 1 << 0;
+    const FLAG_EXCLUDE_KERNEL: u64 = 1 << 5;
+    const FLAG_EXCLUDE_HV: u64 = 1 << 6;
+
+    const PERF_FLAG_FD_CLOEXEC: libc::c_ulong = 1 << 3;
+
+    // _IO('$', n)
+    const PERF_EVENT_IOC_ENABLE: u64 = 0x2400;
+    const PERF_EVENT_IOC_DISABLE: u64 = 0x2401;
+
+    /// First published layout of `struct perf_event_attr` (`PERF_ATTR_SIZE_VER0`), which
+    /// every kernel with `perf_event_open` accepts.
+    #[repr(C)]
+    #[derive(Default)]
+    struct PerfEventAttr {
+        type_: u32,
+        size: u32,
+        config: u64,
+        sample_period: u64,
+        sample_type: u64,
+        read_format: u64,
+        flags: u64,
+        wakeup_events: u32,
+        bp_type: u32,
+        config1: u64,
+    }
+
+    #[derive(Clone, Copy)]
+    enum Event {
+        Instructions,
+        Cycles,
+        BranchMisses,
+        CacheMisses,
+        TaskClock,
+        ContextSwitches,
+    }
+
+    impl Event {
+        const ALL: [Self; 6] = [
+            Self::Instructions,
+            Self::Cycles,
+            Self::BranchMisses,
+            Self::CacheMisses,
+            Self::TaskClock,
+            Self::ContextSwitches,
+        ];
+
+        fn type_and_config(self) -> (u32, u64) {
+            match self {
+                Self::Instructions => (PERF_TYPE_HARDWARE, PERF_COUNT_HW_INSTRUCTIONS),
+                Self::Cycles => (PERF_TYPE_HARDWARE, PERF_COUNT_HW_CPU_CYCLES),
+                Self::BranchMisses => (PERF_TYPE_HARDWARE, PERF_COUNT_HW_BRANCH_MISSES),
+                Self::CacheMisses => (PERF_TYPE_HARDWARE, PERF_COUNT_HW_CACHE_MISSES),
+                Self::TaskClock => (PERF_TYPE_SOFTWARE, PERF_COUNT_SW_TASK_CLOCK),
+                Self::ContextSwitches => (PERF_TYPE_SOFTWARE, PERF_COUNT_SW_CONTEXT_SWITCHES),
+            }
+        }
+    }
+
+    struct Counter {
+        event: Event,
+        fd: OwnedFd,
+    }
+
+    thread_local! {
+        static COUNTERS: RefCell<Vec<Counter>> = const { RefCell::new(Vec::new()) };
+    }
+
+    /// Open `event` for the calling thread on any CPU, initially disabled.
+    fn open_event(event: Event, exclude_kernel: bool) -> Option<OwnedFd> {
+        let (type_, config) = event.type_and_config();
+        let mut flags = FLAG_DISABLED | FLAG_EXCLUDE_HV;
+        if exclude_kernel {
+            flags |= FLAG_EXCLUDE_KERNEL;
+        }
+        let attr = PerfEventAttr {
+            type_,
+            size: size_of::<PerfEventAttr>() as u32,
+            config,
+            read_format: PERF_FORMAT_TOTAL_TIME_ENABLED | PERF_FORMAT_TOTAL_TIME_RUNNING,
+            flags,
+            ..Default::default()
+        };
+
+        // SAFETY: `attr` is a valid `perf_event_attr` whose `size` matches its layout, and
+        // the remaining arguments are plain integers.
+        let fd = unsafe {
+            libc::syscall(libc::SYS_perf_event_open, &attr as *const PerfEventAttr, 0, -1, -1, PERF_FLAG_FD_CLOEXEC)
+        };
+        if fd < 0 {
+            return None;
+        }
+
+        // SAFETY: The syscall returned a new file descriptor that nothing else owns.
+        Some(unsafe { OwnedFd::from_raw_fd(fd as libc::c_int) })
+    }
+
+    pub(super) fn open() -> bool {
+        let counters: Vec<Counter> = Event::ALL
+            .into_iter()
+            .filter_map(|event| {
+                // Hardware counters only count user space, so the ioctls around each batch
+                // don't show up. Software counters need the kernel to count anything, but
+                // fall back to user space when that is not permitted.
+                let fd = match event.type_and_config().0 {
+                    PERF_TYPE_HARDWARE => open_event(event, true),
+                    _ => open_event(event, false).or_else(|| open_event(event, true)),
+                };
+                fd.map(|fd| Counter { event, fd })
+            })
+            .collect();
+
+        let any = !counters.is_empty();
+        COUNTERS.with(|c| *c.borrow_mut() = counters);
+        any
+    }
+
+    pub(super) fn set_counting(enabled: bool) {
+        let request = if enabled { PERF_EVENT_IOC_ENABLE } else { PERF_EVENT_IOC_DISABLE };
+        COUNTERS.with(|counters| {
+            for counter in counters.borrow().iter() {
+                // SAFETY: The descriptor is an open perf event, and these ioctls take no argument.
+                unsafe { libc::ioctl(counter.fd.as_raw_fd(), request as _, 0) };
+            }
+        });
+    }
+
+    /// Read a counter as `(value, multiplexed)`, extrapolating the value if the counter
+    /// only ran for part of the time it was enabled.
+    fn read(fd: &OwnedFd) -> Option<(u64, bool)> {
+        let mut buf = [0u64; 3];
+        // SAFETY: `buf` is valid for writes of its full size.
+        let read = unsafe { libc::read(fd.as_raw_fd(), buf.as_mut_ptr().cast(), size_of_val(&buf)) };
+        if read != size_of_val(&buf) as isize {
+            return None;
+        }
+
+        let [value, enabled, running] = buf;
+        if running == 0 || running == enabled {
+            return Some((value, false));
+        }
+        Some(((value as f64 * enabled as f64 / running as f64) as u64, true))
+    }
+
+    pub(super) fn close() -> Option<PerfCounts> {
+        let counters = COUNTERS.with(|c| std::mem::take(&mut *c.borrow_mut()));
+        if counters.is_empty() {
+            return None;
+        }
+
+        let mut counts = PerfCounts::default();
+        for counter in &counters {
+            let Some((value, multiplexed)) = read(&counter.fd) else {
+                continue;
+            };
+            counts.multiplexed |= multiplexed;
+            let slot = match counter.event {
+                Event::Instructions => &mut counts.instructions,
+                Event::Cycles => &mut counts.cycles,
+                Event::BranchMisses => &mut counts.branch_misses,
+                Event::CacheMisses => &mut counts.cache_misses,
+                Event::TaskClock => &mut counts.task_clock_ns,
+                Event::ContextSwitches => &mut counts.context_switches,
+            };
+            *slot = Some(value);
+        }
+
+        Some(counts)
+    }
+}
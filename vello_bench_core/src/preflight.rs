@@ -0,0 +1,171 @@
+//! Checks for system conditions that make native timings noisy.
+//!
+//! On Linux, this inspects `/proc` and `/sys` for the CPU frequency governor, turbo boost,
+//! load average, SMT and thermal throttling. Other platforms report nothing.
+
+use crate::error::BenchError;
+use serde::{Deserialize, Serialize};
+
+/// One-minute load average per logical core above which other processes are considered busy.
+pub const LOAD_AVERAGE_PER_CORE: f64 = 0.5;
+
+/// Kind of noise a preflight warning is about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NoiseSource {
+    /// A CPU frequency governor other than `performance` is active.
+    Governor,
+    /// Turbo boost is enabled, so clock speeds depend on temperature and load.
+    Turbo,
+    /// Other processes are keeping the CPUs busy.
+    LoadAverage,
+    /// Simultaneous multithreading is enabled, so a sibling thread can share the core.
+    Smt,
+    /// CPUs have been thermally throttled.
+    ThermalThrottling,
+}
+
+/// A condition that is likely to add noise to measurements.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PreflightWarning {
+    pub source: NoiseSource,
+    pub message: String,
+}
+
+/// Result of the preflight checks.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PreflightReport {
+    pub warnings: Vec<PreflightWarning>,
+}
+
+impl PreflightReport {
+    /// Whether no noise sources were found.
+    pub fn is_quiet(&self) -> bool {
+        self.warnings.is_empty()
+    }
+
+    /// Fail with all warnings, for strict mode.
+    pub fn require_quiet(&self) -> Result<(), BenchError> {
+        if self.is_quiet() {
+            return Ok(());
+        }
+        Err(BenchError::NoisySystem { warnings: self.warnings.iter().map(|w| w.message.clone()).collect() })
+    }
+}
+
+/// Run all preflight checks.
+pub fn run_checks() -> PreflightReport {
+    #[cfg(target_os = "linux")]
+    {
+        let warnings = [linux::governor(), linux::turbo(), linux::load_average(), linux::smt(), linux::thermal_throttling()]
+            .into_iter()
+            .flatten()
+            .collect();
+        PreflightReport { warnings }
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        PreflightReport::default()
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use super::{LOAD_AVERAGE_PER_CORE, NoiseSource, PreflightWarning};
+    use std::path::{Path, PathBuf};
+
+    const CPU_DIR: &str = "/sys/devices/system/cpu";
+
+    fn read(path: impl AsRef<Path>) -> Option<String> {
+        std::fs::read_to_string(path).ok().map(|s| s.trim().to_string())
+    }
+
+    /// Directories of the individual CPUs (`cpu0`, `cpu1`, ...).
+    fn cpu_dirs() -> Vec<PathBuf> {
+        let Ok(entries) = std::fs::read_dir(CPU_DIR) else {
+            return Vec::new();
+        };
+
+        entries
+            .flatten()
+            .map(|e| e.path())
+            .filter(|p| {
+                p.file_name()
+                    .and_then(|n| n.to_str())
+                    .and_then(|n| n.strip_prefix("cpu"))
+                    .is_some_and(|n| !n.is_empty() && n.bytes().all(|b| b.is_ascii_digit()))
+            })
+            .collect()
+    }
+
+    fn warning(source: NoiseSource, message: String) -> Option<PreflightWarning> {
+        Some(PreflightWarning { source, message })
+    }
+
+    pub(super) fn governor() -> Option<PreflightWarning> {
+        let mut governors: Vec<String> = cpu_dirs()
+            .iter()
+            .filter_map(|cpu| read(cpu.join("cpufreq/scaling_governor")))
+            .filter(|g| g != "performance")
+            .collect();
+        governors.sort();
+        governors.dedup();
+
+        if governors.is_empty() {
+            return None;
+        }
+        warning(
+            NoiseSource::Governor,
+            format!("CPU frequency governor is {} instead of performance", governors.join(", ")),
+        )
+    }
+
+    pub(super) fn turbo() -> Option<PreflightWarning> {
+        // intel_pstate exposes `no_turbo`, acpi-cpufreq and amd-pstate expose `boost`.
+        let enabled = match read(format!("{CPU_DIR}/intel_pstate/no_turbo")) {
+            Some(no_turbo) => no_turbo == "0",
+            None => read(format!("{CPU_DIR}/cpufreq/boost")).is_some_and(|boost| boost == "1"),
+        };
+
+        if !enabled {
+            return None;
+        }
+        warning(NoiseSource::Turbo, "turbo boost is enabled".to_string())
+    }
+
+    pub(super) fn load_average() -> Option<PreflightWarning> {
+        let load: f64 = read("/proc/loadavg")?.split_whitespace().next()?.parse().ok()?;
+        let cores = std::thread::available_parallelism().map_or(1, |n| n.get());
+
+        // Allow a whole core on small machines, where the threshold would be below one busy
+        // process.
+        if load <= (LOAD_AVERAGE_PER_CORE * cores as f64).max(1.0) {
+            return None;
+        }
+        warning(NoiseSource::LoadAverage, format!("load average is {load:.2} on {cores} logical cores"))
+    }
+
+    pub(super) fn smt() -> Option<PreflightWarning> {
+        if read(format!("{CPU_DIR}/smt/active"))? != "1" {
+            return None;
+        }
+        warning(NoiseSource::Smt, "SMT (hyper-threading) is active".to_string())
+    }
+
+    pub(super) fn thermal_throttling() -> Option<PreflightWarning> {
+        // Only exposed on Intel CPUs; the counts are cumulative since boot.
+        let throttled: u64 = cpu_dirs()
+            .iter()
+            .flat_map(|cpu| ["core_throttle_count", "package_throttle_count"].map(|f| cpu.join("thermal_throttle").join(f)))
+            .filter_map(|path| read(path)?.parse::<u64>().ok())
+            .sum();
+
+        if throttled == 0 {
+            return None;
+        }
+        warning(
+            NoiseSource::ThermalThrottling,
+            format!("CPUs have been thermally throttled {throttled} times since boot"),
+        )
+    }
+}
@@ -1,8 +1,10 @@
-use crate::benchmarks::*;
+use crate::error::BenchError;
+use crate::params::{ParamInfo, Params};
 use crate::result::BenchmarkResult;
 use crate::runner::BenchRunner;
 use fearless_simd::Level;
 use serde::{Deserialize, Serialize};
+use std::sync::{Arc, LazyLock, RwLock};
 
 /// Benchmark info for the frontend/CLI.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -10,83 +12,201 @@ pub struct BenchmarkInfo {
     pub id: String,
     pub category: String,
     pub name: String,
+    /// Labels cutting across categories (e.g., "svg"), for selecting benchmarks.
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// What a timed iteration does, and what is left to untimed setup.
+    #[serde(default)]
+    pub timed: String,
+    /// Parameters that can be overridden when running the benchmark.
+    #[serde(default)]
+    pub params: Vec<ParamInfo>,
+    /// Whether the benchmark runs the same code at every SIMD level, so that running it at
+    /// one level is enough.
+    #[serde(default)]
+    pub level_agnostic: bool,
 }
 
-impl BenchmarkInfo {
-    /// Build a list from static benchmark names.
-    pub fn from_names(category: &str, names: &[&str]) -> Vec<Self> {
-        names
-            .iter()
-            .map(|name| Self {
-                id: format!("{category}/{name}"),
-                category: category.into(),
-                name: (*name).into(),
-            })
-            .collect()
+/// A benchmark that can be listed and run.
+///
+/// `setup` prepares the state that doesn't depend on the SIMD level, untimed, before every
+/// run, from the values of the parameters declared by `params`, and reports assets that
+/// fail to load. `run` builds whatever does depend on the level and hands the measured
+/// closure to the runner.
+pub trait Benchmark: Send + Sync + 'static {
+    /// State prepared by `setup`.
+    type Input;
+
+    /// Category (e.g., "fine/fill").
+    fn category(&self) -> &str;
+
+    /// Name within the category (e.g., "opaque_short").
+    fn name(&self) -> &str;
+
+    /// Full ID (e.g., "fine/fill/opaque_short").
+    fn id(&self) -> String {
+        format!("{}/{}", self.category(), self.name())
     }
 
-    /// Build a list from data items (one benchmark per SVG).
-    pub fn from_data_items(category: &str) -> Vec<Self> {
-        crate::data::get_data_items()
-            .iter()
-            .map(|item| Self {
-                id: format!("{category}/{}", item.name),
-                category: category.into(),
-                name: item.name.clone(),
-            })
-            .collect()
+    /// Labels cutting across categories (e.g., "svg").
+    fn tags(&self) -> &[&str] {
+        &[]
     }
+
+    /// What a timed iteration does, and what is left to untimed setup.
+    fn timed(&self) -> &str;
+
+    /// Parameters, with their defaults and allowed values.
+    fn params(&self) -> Vec<ParamInfo> {
+        Vec::new()
+    }
+
+    /// Whether the workload ignores the SIMD level it is given.
+    fn level_agnostic(&self) -> bool {
+        false
+    }
+
+    /// Metadata shown by the frontends.
+    fn info(&self) -> BenchmarkInfo {
+        BenchmarkInfo {
+            id: self.id(),
+            category: self.category().into(),
+            name: self.name().into(),
+            tags: self.tags().iter().map(|t| t.to_string()).collect(),
+            timed: self.timed().into(),
+            params: self.params(),
+            level_agnostic: self.level_agnostic(),
+        }
+    }
+
+    /// Prepare the input of `run`. `params` holds a value for every declared parameter.
+    fn setup(&self, params: &Params) -> Result<Self::Input, BenchError>;
+
+    /// Measure the benchmark at `level`.
+    fn run(&self, input: &mut Self::Input, runner: &BenchRunner, level: Level) -> BenchmarkResult;
 }
 
-/// Get the complete list of all available benchmarks.
-pub fn get_benchmark_list() -> Vec<BenchmarkInfo> {
-    let mut benchmarks = Vec::new();
-
-    benchmarks.extend(fine::fill::list());
-    benchmarks.extend(fine::gradient::list());
-    benchmarks.extend(fine::image::list());
-    benchmarks.extend(fine::pack::list());
-    benchmarks.extend(fine::strip::list());
-    benchmarks.extend(tile::list());
-    benchmarks.extend(flatten::list());
-    benchmarks.extend(strokes::list());
-    benchmarks.extend(render_strips::list());
-
-    benchmarks
+/// Object-safe view of a `Benchmark`, so benchmarks with different inputs can be stored
+/// together.
+trait RegisteredBenchmark: Send + Sync {
+    fn id(&self) -> String;
+    fn info(&self) -> BenchmarkInfo;
+    fn run(&self, runner: &BenchRunner, level: Level, overrides: &Params) -> Result<BenchmarkResult, BenchError>;
 }
 
-pub fn run_benchmark_by_id(
-    runner: &BenchRunner,
-    id: &str,
-    level: Level,
-) -> Option<BenchmarkResult> {
-    if let Some(name) = id.strip_prefix("fine/fill/") {
-        return fine::fill::run(name, runner, level);
+impl<B: Benchmark> RegisteredBenchmark for B {
+    fn id(&self) -> String {
+        Benchmark::id(self)
     }
-    if let Some(name) = id.strip_prefix("fine/gradient/") {
-        return fine::gradient::run(name, runner, level);
+
+    fn info(&self) -> BenchmarkInfo {
+        Benchmark::info(self)
     }
-    if let Some(name) = id.strip_prefix("fine/image/") {
-        return fine::image::run(name, runner, level);
+
+    fn run(&self, runner: &BenchRunner, level: Level, overrides: &Params) -> Result<BenchmarkResult, BenchError> {
+        let params = Params::resolve(&self.params(), overrides)
+            .map_err(|message| BenchError::InvalidParams { id: self.id(), message })?;
+        let mut input = self.setup(&params)?;
+        // A timed-out run is still a result, with `StopReason::TimedOut`.
+        let mut result = Benchmark::run(self, &mut input, runner, level);
+        result.params = params;
+        result.level_agnostic = self.level_agnostic();
+        Ok(result)
     }
-    if let Some(name) = id.strip_prefix("fine/pack/") {
-        return fine::pack::run(name, runner, level);
+}
+
+/// A set of benchmarks, in registration order.
+#[derive(Default)]
+pub struct Registry {
+    benchmarks: Vec<Arc<dyn RegisteredBenchmark>>,
+}
+
+impl Registry {
+    /// Create an empty registry.
+    pub fn new() -> Self {
+        Self::default()
     }
-    if let Some(name) = id.strip_prefix("fine/strip/") {
-        return fine::strip::run(name, runner, level);
+
+    /// Create a registry holding the benchmarks of this crate.
+    pub fn with_builtin() -> Self {
+        let mut registry = Self::new();
+        crate::benchmarks::register(&mut registry);
+        registry
     }
-    if let Some(name) = id.strip_prefix("tile/") {
-        return tile::run(name, runner, level);
+
+    /// Add a benchmark, replacing any registered benchmark with the same ID.
+    pub fn register(&mut self, benchmark: impl Benchmark) {
+        let id = Benchmark::id(&benchmark);
+        let benchmark: Arc<dyn RegisteredBenchmark> = Arc::new(benchmark);
+        match self.benchmarks.iter().position(|b| b.id() == id) {
+            Some(index) => self.benchmarks[index] = benchmark,
+            None => self.benchmarks.push(benchmark),
+        }
     }
-    if let Some(name) = id.strip_prefix("flatten/") {
-        return flatten::run(name, runner, level);
+
+    /// List all benchmarks.
+    pub fn list(&self) -> Vec<BenchmarkInfo> {
+        self.benchmarks.iter().map(|b| b.info()).collect()
     }
-    if let Some(name) = id.strip_prefix("strokes/") {
-        return strokes::run(name, runner, level);
+
+    /// Get the metadata of the benchmark with the given ID.
+    pub fn info(&self, id: &str) -> Result<BenchmarkInfo, BenchError> {
+        Ok(self.get(id)?.info())
     }
-    if let Some(name) = id.strip_prefix("render_strips/") {
-        return render_strips::run(name, runner, level);
+
+    /// Run the benchmark with the given ID and parameter overrides.
+    pub fn run(
+        &self,
+        runner: &BenchRunner,
+        id: &str,
+        level: Level,
+        overrides: &Params,
+    ) -> Result<BenchmarkResult, BenchError> {
+        self.get(id)?.run(runner, level, overrides)
     }
 
-    None
+    fn get(&self, id: &str) -> Result<Arc<dyn RegisteredBenchmark>, BenchError> {
+        self.benchmarks
+            .iter()
+            .find(|b| b.id() == id)
+            .cloned()
+            .ok_or_else(|| BenchError::UnknownBenchmark { id: id.to_string() })
+    }
+}
+
+impl std::fmt::Debug for Registry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Registry").field("benchmarks", &self.list()).finish()
+    }
+}
+
+/// The registry behind `get_benchmark_list` and `run_benchmark_by_id`, which the frontends
+/// use.
+static REGISTRY: LazyLock<RwLock<Registry>> = LazyLock::new(|| RwLock::new(Registry::with_builtin()));
+
+/// Add a benchmark to the global registry, so that all frontends list and run it.
+pub fn register(benchmark: impl Benchmark) {
+    REGISTRY.write().unwrap().register(benchmark);
+}
+
+/// Get the complete list of all available benchmarks.
+pub fn get_benchmark_list() -> Vec<BenchmarkInfo> {
+    REGISTRY.read().unwrap().list()
+}
+
+/// Get the metadata of a benchmark of the global registry.
+pub fn get_benchmark_info(id: &str) -> Result<BenchmarkInfo, BenchError> {
+    REGISTRY.read().unwrap().info(id)
+}
+
+/// Run a benchmark of the global registry with parameter overrides (empty for the defaults).
+pub fn run_benchmark_by_id(
+    runner: &BenchRunner,
+    id: &str,
+    level: Level,
+    overrides: &Params,
+) -> Result<BenchmarkResult, BenchError> {
+    // Release the lock before running, so registering isn't blocked for a whole benchmark.
+    let benchmark = REGISTRY.read().unwrap().get(id)?;
+    benchmark.run(runner, level, overrides)
 }
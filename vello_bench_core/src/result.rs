@@ -1,22 +1,314 @@
+use crate::alloc::AllocationCounts;
+use crate::environment::RunEnvironment;
+use crate::params::Params;
+use crate::perf::PerfCounts;
+use crate::stats::{self, Fences};
 use serde::{Deserialize, Serialize};
 
 /// Statistics from a benchmark run.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+///
+/// All times are per iteration, computed over the timed samples of the measurement phase.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Statistics {
     /// Mean time in nanoseconds.
     pub mean_ns: f64,
+    /// Median time in nanoseconds.
+    pub median_ns: f64,
+    /// Sample standard deviation in nanoseconds.
+    pub std_dev_ns: f64,
+    /// Median absolute deviation in nanoseconds.
+    pub mad_ns: f64,
+    /// Fastest sample in nanoseconds.
+    pub min_ns: f64,
+    /// Slowest sample in nanoseconds.
+    pub max_ns: f64,
+    /// 5th percentile in nanoseconds.
+    pub p5_ns: f64,
+    /// 95th percentile in nanoseconds.
+    pub p95_ns: f64,
     /// Number of iterations.
     pub iterations: usize,
+    /// Number of timed samples the iterations were split into.
+    pub sample_count: usize,
+    /// Bootstrap confidence interval of the mean.
+    pub mean_ci: ConfidenceInterval,
+    /// Bootstrap confidence interval of the median.
+    pub median_ci: ConfidenceInterval,
 }
 
 impl Statistics {
-    /// Create statistics from a single measurement.
+    /// Create statistics from a single measurement of `iterations` iterations, as one
+    /// sample.
     pub fn from_measurement(total_time_ns: f64, iterations: usize) -> Self {
+        Self::from_samples(&[total_time_ns / iterations as f64], iterations)
+    }
+
+    /// Create statistics from per-iteration sample times.
+    pub fn from_samples(samples_ns: &[f64], iterations: usize) -> Self {
+        let sorted = stats::sorted(samples_ns);
+
         Self {
-            mean_ns: total_time_ns / iterations as f64,
+            mean_ns: stats::mean(&sorted),
+            median_ns: stats::median(&sorted),
+            std_dev_ns: stats::std_dev(&sorted),
+            mad_ns: stats::mad(&sorted),
+            min_ns: sorted.first().copied().unwrap_or(0.0),
+            max_ns: sorted.last().copied().unwrap_or(0.0),
+            p5_ns: stats::percentile(&sorted, 0.05),
+            p95_ns: stats::percentile(&sorted, 0.95),
             iterations,
+            sample_count: sorted.len(),
+            mean_ci: ConfidenceInterval::default(),
+            median_ci: ConfidenceInterval::default(),
         }
     }
+
+    /// Attach bootstrap confidence intervals for the mean and median of `samples_ns`.
+    pub fn with_confidence_intervals(mut self, samples_ns: &[f64], level: f64, resamples: usize) -> Self {
+        self.mean_ci = ConfidenceInterval::bootstrap(samples_ns, level, resamples, |s| stats::mean(s));
+        self.median_ci = ConfidenceInterval::bootstrap(samples_ns, level, resamples, |s| {
+            s.sort_by(f64::total_cmp);
+            stats::median(s)
+        });
+        self
+    }
+}
+
+/// Confidence interval of an estimate, in nanoseconds.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ConfidenceInterval {
+    /// Lower bound in nanoseconds.
+    pub lower_ns: f64,
+    /// Upper bound in nanoseconds.
+    pub upper_ns: f64,
+    /// Confidence level (e.g., 0.95). Zero if no interval was computed.
+    pub level: f64,
+}
+
+impl ConfidenceInterval {
+    /// Bootstrap the interval of `statistic` over `samples_ns`.
+    pub fn bootstrap<F>(samples_ns: &[f64], level: f64, resamples: usize, statistic: F) -> Self
+    where
+        F: FnMut(&mut [f64]) -> f64,
+    {
+        let (lower_ns, upper_ns) = stats::bootstrap(samples_ns, resamples, level, statistic);
+        Self { lower_ns, upper_ns, level }
+    }
+
+    /// Width of the interval relative to `estimate`.
+    pub fn relative_width(&self, estimate: f64) -> f64 {
+        if estimate == 0.0 {
+            return 0.0;
+        }
+
+        (self.upper_ns - self.lower_ns) / estimate
+    }
+}
+
+/// Number of samples outside the Tukey fences, by side and severity.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Outliers {
+    /// Samples below `Q1 - 3 * IQR`.
+    pub low_severe: usize,
+    /// Samples between `Q1 - 3 * IQR` and `Q1 - 1.5 * IQR`.
+    pub low_mild: usize,
+    /// Samples between `Q3 + 1.5 * IQR` and `Q3 + 3 * IQR`.
+    pub high_mild: usize,
+    /// Samples above `Q3 + 3 * IQR`.
+    pub high_severe: usize,
+    /// Whether the outliers were left out of the statistics.
+    pub excluded: bool,
+}
+
+impl Outliers {
+    /// Classify the given samples.
+    pub fn classify(samples_ns: &[f64]) -> Self {
+        let fences = Fences::new(&stats::sorted(samples_ns));
+        let mut outliers = Self::default();
+
+        for &sample in samples_ns {
+            if sample < fences.low_severe {
+                outliers.low_severe += 1;
+            } else if sample < fences.low_mild {
+                outliers.low_mild += 1;
+            } else if sample > fences.high_severe {
+                outliers.high_severe += 1;
+            } else if sample > fences.high_mild {
+                outliers.high_mild += 1;
+            }
+        }
+
+        outliers
+    }
+
+    /// Total number of outliers.
+    pub fn total(&self) -> usize {
+        self.low_severe + self.low_mild + self.high_mild + self.high_severe
+    }
+}
+
+/// Why the measurement phase stopped.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StopReason {
+    /// The fixed measurement time elapsed.
+    #[default]
+    MeasurementTime,
+    /// The confidence interval of the mean became narrower than the target precision.
+    TargetPrecision,
+    /// The time budget ran out before the target precision was reached.
+    TimeBudget,
+    /// Sampling was cut short by the runner's iteration limit.
+    Truncated,
+    /// The per-benchmark timeout elapsed. If it did so before the measurement phase,
+    /// the statistics come from the calibration batches.
+    TimedOut,
+    /// The runner's fixed iteration count was run.
+    IterationCount,
+}
+
+/// A batch run during the calibration phase.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct CalibrationBatch {
+    /// Number of iterations in the batch.
+    pub iterations: usize,
+    /// Total time of the batch in nanoseconds.
+    pub elapsed_ns: f64,
+}
+
+/// What happened before the measurement phase, for diagnosing cold-start effects.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RunDiagnostics {
+    /// Number of iterations run during warm-up.
+    pub warmup_iterations: usize,
+    /// Time spent warming up in nanoseconds.
+    pub warmup_ns: f64,
+    /// Calibration batches, in the order they ran.
+    pub calibration_batches: Vec<CalibrationBatch>,
+    /// Whether the timeout elapsed before the measurement phase, leaving only the
+    /// calibration batches as samples.
+    pub timed_out: bool,
+}
+
+/// What a benchmark processes per iteration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ThroughputUnit {
+    Pixels,
+    Lines,
+    Segments,
+    Strips,
+}
+
+/// Throughput of a benchmark, derived from its mean time.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Throughput {
+    /// Unit of work.
+    pub unit: ThroughputUnit,
+    /// Units processed per iteration.
+    pub per_iteration: u64,
+    /// Mean nanoseconds per unit.
+    pub ns_per_unit: f64,
+    /// Units processed per second.
+    pub units_per_second: f64,
+}
+
+impl Throughput {
+    /// Compute the throughput for `per_iteration` units at the given mean iteration time.
+    pub fn new(unit: ThroughputUnit, per_iteration: u64, mean_ns: f64) -> Self {
+        let per_iteration_f = per_iteration as f64;
+
+        Self {
+            unit,
+            per_iteration,
+            ns_per_unit: if per_iteration == 0 { 0.0 } else { mean_ns / per_iteration_f },
+            units_per_second: if mean_ns == 0.0 { 0.0 } else { per_iteration_f * 1e9 / mean_ns },
+        }
+    }
+}
+
+/// Allocator activity per iteration, counted over the timed part of the measurement phase.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct AllocationStats {
+    /// Allocations per iteration.
+    pub allocations: f64,
+    /// Deallocations per iteration.
+    pub deallocations: f64,
+    /// Bytes allocated per iteration.
+    pub bytes_allocated: f64,
+}
+
+impl AllocationStats {
+    /// Average raw allocator counts over `iterations`.
+    pub fn per_iteration(counts: AllocationCounts, iterations: usize) -> Self {
+        let iterations = iterations.max(1) as f64;
+
+        Self {
+            allocations: counts.allocations as f64 / iterations,
+            deallocations: counts.deallocations as f64 / iterations,
+            bytes_allocated: counts.bytes_allocated as f64 / iterations,
+        }
+    }
+}
+
+/// Performance counter readings per iteration, counted over the timed part of the
+/// measurement phase. Counters that could not be opened are `None`.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CounterStats {
+    /// Retired user-space instructions per iteration.
+    pub instructions: Option<f64>,
+    /// User-space CPU cycles per iteration.
+    pub cycles: Option<f64>,
+    /// Mispredicted branches per iteration.
+    pub branch_misses: Option<f64>,
+    /// Last-level cache misses per iteration.
+    pub cache_misses: Option<f64>,
+    /// Nanoseconds the thread was on a CPU, per iteration.
+    pub task_clock_ns: Option<f64>,
+    /// Context switches per iteration.
+    pub context_switches: Option<f64>,
+    /// Whether counters had to share hardware, so their values are extrapolated.
+    pub multiplexed: bool,
+}
+
+impl CounterStats {
+    /// Average raw counter totals over `iterations`.
+    pub fn per_iteration(counts: PerfCounts, iterations: usize) -> Self {
+        let iterations = iterations.max(1) as f64;
+        let per_iteration = |count: Option<u64>| count.map(|c| c as f64 / iterations);
+
+        Self {
+            instructions: per_iteration(counts.instructions),
+            cycles: per_iteration(counts.cycles),
+            branch_misses: per_iteration(counts.branch_misses),
+            cache_misses: per_iteration(counts.cache_misses),
+            task_clock_ns: per_iteration(counts.task_clock_ns),
+            context_switches: per_iteration(counts.context_switches),
+            multiplexed: counts.multiplexed,
+        }
+    }
+}
+
+/// Instruction and cache miss counts per iteration of a benchmark, measured under Cachegrind.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachegrindResult {
+    /// Full benchmark ID (e.g., "fine/fill/opaque_short").
+    pub id: String,
+    /// SIMD variant used (e.g., "avx2", "scalar").
+    pub simd_variant: String,
+    /// Number of iterations the counts are averaged over.
+    pub iterations: usize,
+    /// Instructions executed per iteration.
+    pub instructions: f64,
+    /// L1 instruction and data cache misses per iteration.
+    pub l1_misses: f64,
+    /// Last-level cache misses per iteration.
+    pub ll_misses: f64,
 }
 
 /// Result from running a single benchmark.
@@ -30,8 +322,148 @@ pub struct BenchmarkResult {
     pub name: String,
     /// SIMD variant used (e.g., "u8_neon", "scalar").
     pub simd_variant: String,
+    /// Id of the timer used (e.g., "wall", "thread_cpu").
+    #[serde(default)]
+    pub timer: String,
     /// Benchmark statistics.
     pub statistics: Statistics,
+    /// Outliers among the samples.
+    #[serde(default)]
+    pub outliers: Outliers,
+    /// Why the measurement phase stopped.
+    #[serde(default)]
+    pub stop_reason: StopReason,
+    /// Warm-up and calibration details.
+    #[serde(default)]
+    pub diagnostics: RunDiagnostics,
+    /// Throughput, for benchmarks that declare how much work an iteration does.
+    #[serde(default)]
+    pub throughput: Option<Throughput>,
+    /// Allocator activity, when built with the `alloc-tracking` feature.
+    #[serde(default)]
+    pub allocations: Option<AllocationStats>,
+    /// Performance counters, when requested and permitted by the OS.
+    #[serde(default)]
+    pub counters: Option<CounterStats>,
+    /// Core the benchmark thread was pinned to, if pinning was requested and succeeded.
+    #[serde(default)]
+    pub pinned_core: Option<usize>,
+    /// Nice value the benchmark ran with, if raising its priority was requested and allowed.
+    #[serde(default)]
+    pub nice: Option<i32>,
+    /// Mean time per iteration spent in untimed setup, for benchmarks that have any.
+    #[serde(default)]
+    pub setup_mean_ns: Option<f64>,
+    /// Mean time per iteration of each timed sample, in measurement order.
+    #[serde(default)]
+    pub samples_ns: Vec<f64>,
+    /// Values of the benchmark's parameters.
+    #[serde(default)]
+    pub params: Params,
+    /// Whether the benchmark ignores the SIMD level, so that `simd_variant` is only the
+    /// level it happened to run at.
+    #[serde(default)]
+    pub level_agnostic: bool,
     /// Timestamp when benchmark was run (milliseconds since epoch).
     pub timestamp_ms: u64,
 }
+
+impl BenchmarkResult {
+    /// Declare that each iteration processes `per_iteration` units of `unit`.
+    pub fn with_throughput(mut self, unit: ThroughputUnit, per_iteration: u64) -> Self {
+        self.throughput = Some(Throughput::new(unit, per_iteration, self.statistics.mean_ns));
+        self
+    }
+}
+
+/// A set of results together with the environment they were measured in, as saved to
+/// reference and export files.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ResultSet {
+    /// Machine, build and runner settings, if recorded.
+    #[serde(default)]
+    pub environment: Option<RunEnvironment>,
+    pub results: Vec<BenchmarkResult>,
+}
+
+impl ResultSet {
+    /// Parse a saved result set, also accepting the bare result arrays of older files.
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Saved {
+            Set(Box<ResultSet>),
+            Legacy(Vec<BenchmarkResult>),
+        }
+
+        Ok(match serde_json::from_str(json)? {
+            Saved::Set(set) => *set,
+            Saved::Legacy(results) => Self { environment: None, results },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runner::BenchRunner;
+
+    const LEGACY_RESULT: &str = r#"{
+        "id": "fine/fill",
+        "category": "fine",
+        "name": "fill",
+        "simd_variant": "scalar",
+        "statistics": { "mean_ns": 120.0, "iterations": 1000 },
+        "timestamp_ms": 1700000000000
+    }"#;
+
+    #[test]
+    fn from_measurement_is_a_single_sample() {
+        let statistics = Statistics::from_measurement(1000.0, 10);
+        assert_eq!(statistics.mean_ns, 100.0);
+        assert_eq!(statistics.median_ns, 100.0);
+        assert_eq!(statistics.std_dev_ns, 0.0);
+        assert_eq!(statistics.iterations, 10);
+        assert_eq!(statistics.sample_count, 1);
+    }
+
+    #[test]
+    fn from_json_accepts_legacy_arrays() {
+        let set = ResultSet::from_json(&format!("[{LEGACY_RESULT}]")).unwrap();
+        assert!(set.environment.is_none());
+        assert_eq!(set.results.len(), 1);
+
+        let result = &set.results[0];
+        assert_eq!(result.id, "fine/fill");
+        assert_eq!(result.statistics.mean_ns, 120.0);
+        assert_eq!(result.statistics.iterations, 1000);
+        assert_eq!(result.stop_reason, StopReason::default());
+        assert!(result.samples_ns.is_empty());
+        assert!(result.params.is_empty());
+
+        assert!(ResultSet::from_json("[]").unwrap().results.is_empty());
+    }
+
+    #[test]
+    fn from_json_reads_result_sets() {
+        let environment = RunEnvironment::capture(&BenchRunner::new(100, 200));
+        let json = format!(
+            r#"{{ "environment": {}, "results": [{LEGACY_RESULT}] }}"#,
+            serde_json::to_string(&environment).unwrap()
+        );
+        let set = ResultSet::from_json(&json).unwrap();
+        assert_eq!(set.environment.unwrap().arch, environment.arch);
+        assert_eq!(set.results[0].id, "fine/fill");
+
+        let set = ResultSet::from_json(&format!(r#"{{ "results": [{LEGACY_RESULT}] }}"#)).unwrap();
+        assert!(set.environment.is_none());
+        assert_eq!(set.results.len(), 1);
+    }
+
+    #[test]
+    fn from_json_rejects_other_documents() {
+        assert!(ResultSet::from_json(LEGACY_RESULT).is_err());
+        assert!(ResultSet::from_json(r#"{ "environment": null }"#).is_err());
+        assert!(ResultSet::from_json("not json").is_err());
+    }
+}
@@ -1,94 +1,570 @@
-use crate::result::{BenchmarkResult, Statistics};
+use crate::affinity::ThreadPlacement;
+use crate::alloc;
+use crate::error::BenchError;
+use crate::interleave::Turn;
+use crate::params::Params;
+use crate::perf;
+use crate::preflight;
+use crate::result::{AllocationStats, BenchmarkResult, CalibrationBatch, CounterStats, Outliers, RunDiagnostics, Statistics, StopReason};
+use crate::stats::{self, Fences};
+use crate::timer::{Timer, platform_timer, timer_from_id};
+use crate::validate::Output;
+use serde::{Deserialize, Serialize};
+use std::cell::{Cell, RefCell};
+use std::fmt::Debug;
+use std::sync::Arc;
+
+/// Number of inputs `BenchRunner::run_batched` prepares before timing the routine on them.
+pub const SETUP_BATCH_SIZE: usize = 16;
+/// Default duration of the warm-up phase.
+pub const DEFAULT_WARMUP_MS: u64 = 50;
+/// Default number of timed samples the measurement phase is split into.
+pub const DEFAULT_SAMPLE_COUNT: usize = 50;
+/// Default confidence level of the reported intervals.
+pub const DEFAULT_CONFIDENCE_LEVEL: f64 = 0.95;
+/// Default number of bootstrap resamples used to estimate confidence intervals.
+pub const DEFAULT_BOOTSTRAP_RESAMPLES: usize = 1000;
+/// Default time budget of the measurement phase when measuring to a target precision.
+pub const DEFAULT_MAX_TIME_MS: u64 = 10_000;
+/// Default wall-clock limit for a single benchmark, from warm-up to the last sample.
+pub const DEFAULT_TIMEOUT_MS: u64 = 60_000;
 
 #[derive(Debug, Clone)]
 pub struct BenchRunner {
+    /// Time spent running the benchmark untimed before calibration, to fault in caches,
+    /// buffers and branch predictors.
+    pub warmup_ms: u64,
     pub calibration_ms: u64,
     pub measurement_ms: u64,
+    /// Number of timed samples the measurement phase is split into.
+    pub sample_count: usize,
+    /// Leave samples outside the mild Tukey fences out of the statistics.
+    pub exclude_outliers: bool,
+    /// Confidence level of the reported intervals (e.g., 0.95).
+    pub confidence_level: f64,
+    /// Number of bootstrap resamples used to estimate confidence intervals.
+    pub bootstrap_resamples: usize,
+    /// If set, keep sampling until the relative width of the mean's confidence interval
+    /// drops below this value (e.g., 0.01 for 1%) instead of stopping after `measurement_ms`.
+    pub target_precision: Option<f64>,
+    /// Time budget of the measurement phase when measuring to a target precision.
+    pub max_time_ms: u64,
+    /// Upper bound on the iterations of any warm-up, calibration or measurement run.
+    pub max_iterations: Option<usize>,
+    /// Wall-clock limit for the whole benchmark. It is checked between batches and samples,
+    /// so a single iteration that never returns cannot be interrupted.
+    pub timeout_ms: Option<u64>,
+    /// Skip warm-up and calibration and time exactly this many iterations as a single
+    /// sample, ignoring all time-based settings. Meant for instruction counting under
+    /// Cachegrind, where only the amount of work matters.
+    pub fixed_iterations: Option<usize>,
+    /// Record Linux performance counters during the measurement phase (see `perf`).
+    pub perf_counters: bool,
+    /// Pin the benchmark thread to this core for the duration of each run (Linux only).
+    pub pin_core: Option<usize>,
+    /// Run with the highest scheduling priority the process may set (Linux only).
+    pub raise_priority: bool,
+    /// Refuse to run while the preflight checks find noise sources (see `check_preflight`).
+    pub strict: bool,
+    /// Clock used for all timing, including the timeout.
+    pub timer: Arc<dyn Timer>,
+    /// Set for the runners of an interleaved run (see `interleave`), which hand over to
+    /// each other after every measurement sample.
+    pub(crate) turn: Option<Turn>,
+    /// Outputs recorded with `record_output`, when validating SIMD levels (see `validate`).
+    pub(crate) outputs: Option<RefCell<Vec<Output>>>,
 }
 
 impl BenchRunner {
     pub fn new(calibration_ms: u64, measurement_ms: u64) -> Self {
-        Self { calibration_ms, measurement_ms }
+        Self {
+            warmup_ms: DEFAULT_WARMUP_MS,
+            calibration_ms,
+            measurement_ms,
+            sample_count: DEFAULT_SAMPLE_COUNT,
+            exclude_outliers: false,
+            confidence_level: DEFAULT_CONFIDENCE_LEVEL,
+            bootstrap_resamples: DEFAULT_BOOTSTRAP_RESAMPLES,
+            target_precision: None,
+            max_time_ms: DEFAULT_MAX_TIME_MS,
+            max_iterations: None,
+            timeout_ms: Some(DEFAULT_TIMEOUT_MS),
+            fixed_iterations: None,
+            perf_counters: false,
+            pin_core: None,
+            raise_priority: false,
+            strict: false,
+            timer: platform_timer(),
+            turn: None,
+            outputs: None,
+        }
+    }
+
+    /// Use `timer` instead of the platform's default timer.
+    pub fn with_timer(mut self, timer: impl Timer + 'static) -> Self {
+        self.timer = Arc::new(timer);
+        self
+    }
+}
+
+/// Snapshot of a runner's settings, recorded with results (see `RunEnvironment`).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RunnerSettings {
+    pub warmup_ms: u64,
+    pub calibration_ms: u64,
+    pub measurement_ms: u64,
+    pub sample_count: usize,
+    pub exclude_outliers: bool,
+    pub confidence_level: f64,
+    pub bootstrap_resamples: usize,
+    pub target_precision: Option<f64>,
+    pub max_time_ms: u64,
+    pub max_iterations: Option<usize>,
+    pub timeout_ms: Option<u64>,
+    pub fixed_iterations: Option<usize>,
+    pub perf_counters: bool,
+    pub pin_core: Option<usize>,
+    pub raise_priority: bool,
+    pub strict: bool,
+    /// Id of the timer.
+    pub timer: String,
+}
+
+impl BenchRunner {
+    /// Create a runner with the given settings. An unknown timer id falls back to the
+    /// platform's default timer.
+    pub fn from_settings(settings: &RunnerSettings) -> Self {
+        Self {
+            warmup_ms: settings.warmup_ms,
+            calibration_ms: settings.calibration_ms,
+            measurement_ms: settings.measurement_ms,
+            sample_count: settings.sample_count,
+            exclude_outliers: settings.exclude_outliers,
+            confidence_level: settings.confidence_level,
+            bootstrap_resamples: settings.bootstrap_resamples,
+            target_precision: settings.target_precision,
+            max_time_ms: settings.max_time_ms,
+            max_iterations: settings.max_iterations,
+            timeout_ms: settings.timeout_ms,
+            fixed_iterations: settings.fixed_iterations,
+            perf_counters: settings.perf_counters,
+            pin_core: settings.pin_core,
+            raise_priority: settings.raise_priority,
+            strict: settings.strict,
+            timer: timer_from_id(&settings.timer).unwrap_or_else(platform_timer),
+            turn: None,
+            outputs: None,
+        }
+    }
+
+    /// Snapshot the current settings.
+    pub fn settings(&self) -> RunnerSettings {
+        RunnerSettings {
+            warmup_ms: self.warmup_ms,
+            calibration_ms: self.calibration_ms,
+            measurement_ms: self.measurement_ms,
+            sample_count: self.sample_count,
+            exclude_outliers: self.exclude_outliers,
+            confidence_level: self.confidence_level,
+            bootstrap_resamples: self.bootstrap_resamples,
+            target_precision: self.target_precision,
+            max_time_ms: self.max_time_ms,
+            max_iterations: self.max_iterations,
+            timeout_ms: self.timeout_ms,
+            fixed_iterations: self.fixed_iterations,
+            perf_counters: self.perf_counters,
+            pin_core: self.pin_core,
+            raise_priority: self.raise_priority,
+            strict: self.strict,
+            timer: self.timer.id().to_string(),
+        }
+    }
+}
+
+/// Start counting allocations and performance events on the current thread.
+fn begin_timed_region() {
+    alloc::set_tracking(true);
+    perf::set_counting(true);
+}
+
+/// Stop counting allocations and performance events on the current thread.
+fn end_timed_region() {
+    perf::set_counting(false);
+    alloc::set_tracking(false);
+}
+
+/// Optional overrides of the `BenchRunner` defaults, as sent by the frontends.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RunnerOptions {
+    pub warmup_ms: Option<u64>,
+    pub exclude_outliers: Option<bool>,
+    pub confidence_level: Option<f64>,
+    pub target_precision: Option<f64>,
+    pub max_time_ms: Option<u64>,
+    pub max_iterations: Option<usize>,
+    pub timeout_ms: Option<u64>,
+    pub perf_counters: Option<bool>,
+    pub pin_core: Option<usize>,
+    pub raise_priority: Option<bool>,
+    pub strict: Option<bool>,
+    /// Id of the timer to use (see `available_timer_infos`). Unknown ids are ignored.
+    pub timer: Option<String>,
+}
+
+impl BenchRunner {
+    /// Apply the overrides that are set in `options`.
+    pub fn with_options(mut self, options: &RunnerOptions) -> Self {
+        if let Some(warmup_ms) = options.warmup_ms {
+            self.warmup_ms = warmup_ms;
+        }
+        if let Some(exclude_outliers) = options.exclude_outliers {
+            self.exclude_outliers = exclude_outliers;
+        }
+        if let Some(confidence_level) = options.confidence_level {
+            self.confidence_level = confidence_level;
+        }
+        if options.target_precision.is_some() {
+            self.target_precision = options.target_precision;
+        }
+        if let Some(max_time_ms) = options.max_time_ms {
+            self.max_time_ms = max_time_ms;
+        }
+        if options.max_iterations.is_some() {
+            self.max_iterations = options.max_iterations;
+        }
+        if options.timeout_ms.is_some() {
+            self.timeout_ms = options.timeout_ms;
+        }
+        if let Some(perf_counters) = options.perf_counters {
+            self.perf_counters = perf_counters;
+        }
+        if options.pin_core.is_some() {
+            self.pin_core = options.pin_core;
+        }
+        if let Some(raise_priority) = options.raise_priority {
+            self.raise_priority = raise_priority;
+        }
+        if let Some(strict) = options.strict {
+            self.strict = strict;
+        }
+        if let Some(timer) = options.timer.as_deref().and_then(timer_from_id) {
+            self.timer = timer;
+        }
+        self
+    }
+
+    /// In strict mode, fail if the preflight checks find noise sources.
+    ///
+    /// Call this once before the first benchmark of a run rather than before each one, since
+    /// the load of the earlier benchmarks would count as noise.
+    pub fn check_preflight(&self) -> Result<(), BenchError> {
+        if !self.strict {
+            return Ok(());
+        }
+        preflight::run_checks().require_quiet()
     }
 }
 
 impl BenchRunner {
-    fn calibrate<F, T: Timer>(&self, timer: &T, mut f: F) -> (usize, f64)
+    /// Whether the per-benchmark timeout has elapsed since `run_start`.
+    fn timed_out(&self, run_start: f64) -> bool {
+        self.timeout_ms
+            .is_some_and(|timeout_ms| self.timer.elapsed_ns(run_start) >= timeout_ms as f64 * 1_000_000.0)
+    }
+
+    /// Time left until the per-benchmark timeout, or infinity if there is none.
+    fn time_left_ns(&self, run_start: f64) -> f64 {
+        self.timeout_ms
+            .map_or(f64::INFINITY, |timeout_ms| timeout_ms as f64 * 1_000_000.0 - self.timer.elapsed_ns(run_start))
+    }
+
+    /// Run the benchmark untimed for `warmup_ms`, returning the number of iterations and
+    /// the time spent.
+    ///
+    /// Like calibration batches, warm-up batches double in size but never beyond what is left
+    /// of the warm-up time or the timeout (judging by the previous batch).
+    fn warm_up<B>(&self, run_start: f64, mut batch: B) -> (usize, f64)
     where
-        F: FnMut(),
+        B: FnMut(usize) -> f64,
+    {
+        let timer = &*self.timer;
+        let target_ns = self.warmup_ms as f64 * 1_000_000.0;
+        let start = timer.now();
+        let mut iterations = 0;
+        let mut batch_size = 1usize;
+
+        // Grow the batches so that reading the timer doesn't dominate fast benchmarks.
+        while timer.elapsed_ns(start) < target_ns && !self.timed_out(run_start) {
+            let remaining = self.max_iterations.map_or(usize::MAX, |max| max.saturating_sub(iterations));
+            if remaining == 0 {
+                break;
+            }
+            let size = batch_size.min(remaining);
+            let elapsed_ns = batch(size);
+            iterations += size;
+
+            let time_left_ns = (target_ns - timer.elapsed_ns(start)).min(self.time_left_ns(run_start));
+            let remaining_iters = time_left_ns * size as f64 / elapsed_ns;
+            batch_size = batch_size.saturating_mul(2).min(remaining_iters.ceil() as usize).max(1);
+        }
+
+        (iterations, timer.elapsed_ns(start))
+    }
+
+    /// Run growing batches until they add up to `calibration_ms`, and return them.
+    ///
+    /// Batches double in size, but never beyond what is left of the calibration time
+    /// (judging by the previous batch) or `max_iterations`, so slow benchmarks don't
+    /// overshoot the target. Calibration also stops once the timeout has elapsed.
+    fn calibrate<B>(&self, run_start: f64, mut batch: B) -> Vec<CalibrationBatch>
+    where
+        B: FnMut(usize) -> f64,
     {
         let target_ns = self.calibration_ms as f64 * 1_000_000.0;
+        let max_batch_size = self.max_iterations.unwrap_or(usize::MAX).max(1);
+        let mut batches = Vec::new();
+        let mut total_ns = 0.0;
         let mut batch_size = 1usize;
 
         loop {
-            let start = timer.now();
-            for _ in 0..batch_size {
-                f();
-            }
-            let elapsed_ns = timer.elapsed_ns(start);
+            let elapsed_ns = batch(batch_size);
+            batches.push(CalibrationBatch { iterations: batch_size, elapsed_ns });
+            total_ns += elapsed_ns;
 
-            if elapsed_ns >= target_ns {
-                return (batch_size, elapsed_ns);
+            if total_ns >= target_ns || self.timed_out(run_start) {
+                return batches;
             }
 
-            batch_size *= 2;
+            let remaining_iters = (target_ns - total_ns) * batch_size as f64 / elapsed_ns;
+            let next = batch_size.saturating_mul(2).min(remaining_iters.ceil() as usize);
+            batch_size = next.clamp(1, max_batch_size);
         }
     }
 
-    /// Run the measurement phase and return statistics.
-    fn measure<F, T: Timer>(timer: &T, mut f: F, total_iters: usize) -> Statistics
+    /// Run the measurement phase and return the mean time per iteration of each sample,
+    /// together with the reason sampling stopped.
+    ///
+    /// Without a target precision, this takes `sample_count` samples. Otherwise, sampling
+    /// continues until the target precision or the time budget is reached. Either way,
+    /// sampling stops early once `max_iterations` or the timeout is reached.
+    fn measure<B>(
+        &self,
+        run_start: f64,
+        mut batch: B,
+        sample_count: usize,
+        iters_per_sample: usize,
+    ) -> (Vec<f64>, StopReason)
     where
-        F: FnMut(),
+        B: FnMut(usize) -> f64,
     {
+        let timer = &*self.timer;
+        let mut sample = || batch(iters_per_sample) / iters_per_sample as f64;
+
+        let max_samples = self.max_iterations.map_or(usize::MAX, |max| max / iters_per_sample).max(1);
+        let budget_ns = self.max_time_ms as f64 * 1_000_000.0;
+        let min_samples = sample_count.max(2);
         let start = timer.now();
-        for _ in 0..total_iters {
-            f();
+        let mut samples = Vec::with_capacity(sample_count);
+        let mut next_check = min_samples;
+
+        loop {
+            samples.push(sample());
+
+            if let Some(turn) = &self.turn {
+                turn.yield_turn();
+            }
+
+            if self.timed_out(run_start) {
+                return (samples, StopReason::TimedOut);
+            }
+
+            let Some(target_precision) = self.target_precision else {
+                if samples.len() >= sample_count {
+                    return (samples, StopReason::MeasurementTime);
+                }
+                if samples.len() >= max_samples {
+                    return (samples, StopReason::Truncated);
+                }
+                continue;
+            };
+
+            if samples.len() >= next_check {
+                let (statistics, _) = self.summarize(&samples, samples.len() * iters_per_sample);
+                if statistics.mean_ci.relative_width(statistics.mean_ns) <= target_precision {
+                    return (samples, StopReason::TargetPrecision);
+                }
+                // Re-check after every ~10% of growth so the bootstrap cost stays small.
+                next_check = samples.len() + (samples.len() / 10).max(1);
+            }
+
+            if samples.len() >= max_samples {
+                return (samples, StopReason::Truncated);
+            }
+            if timer.elapsed_ns(start) >= budget_ns {
+                return (samples, StopReason::TimeBudget);
+            }
         }
-        let elapsed_ns = timer.elapsed_ns(start);
+    }
 
-        Statistics::from_measurement(elapsed_ns, total_iters)
+    /// Classify outliers and compute statistics, leaving outliers out if requested.
+    fn summarize(&self, samples_ns: &[f64], iterations: usize) -> (Statistics, Outliers) {
+        let mut outliers = Outliers::classify(samples_ns);
+
+        if !self.exclude_outliers || outliers.total() == 0 {
+            return (self.statistics(samples_ns, iterations), outliers);
+        }
+
+        let fences = Fences::new(&stats::sorted(samples_ns));
+        let kept: Vec<f64> = samples_ns.iter().copied().filter(|s| !fences.is_outlier(*s)).collect();
+        let kept_iterations = iterations / samples_ns.len() * kept.len();
+        outliers.excluded = true;
+
+        (self.statistics(&kept, kept_iterations), outliers)
+    }
+
+    /// Compute statistics, including confidence intervals, of the given samples.
+    fn statistics(&self, samples_ns: &[f64], iterations: usize) -> Statistics {
+        Statistics::from_samples(samples_ns, iterations).with_confidence_intervals(
+            samples_ns,
+            self.confidence_level,
+            self.bootstrap_resamples,
+        )
     }
 
-    /// Run a benchmark using the provided timer, with optional callback after calibration.
-    fn run_with_timer<F, T: Timer, C: FnOnce()>(
+    /// Run a benchmark, with optional callback after calibration.
+    ///
+    /// `batch` runs the given number of iterations and returns the time they took. It should
+    /// call `begin_timed_region` and `end_timed_region` around the code it times.
+    fn run_inner<B, C: FnOnce()>(
         &self,
-        timer: &T,
         id: &str,
         category: &str,
         name: &str,
         simd_variant: &str,
-        mut f: F,
+        mut batch: B,
         on_calibrated: C,
     ) -> BenchmarkResult
     where
-        F: FnMut(),
+        B: FnMut(usize) -> f64,
     {
-        let (batch_size, batch_time_ns) = self.calibrate(timer, &mut f);
+        let timer = &*self.timer;
+        let placement = ThreadPlacement::apply(self.pin_core, self.raise_priority);
+        let run_start = timer.now();
+        let (warmup_iterations, warmup_ns, calibration_batches) = if self.fixed_iterations.is_some() {
+            (0, 0.0, Vec::new())
+        } else {
+            let (warmup_iterations, warmup_ns) = self.warm_up(run_start, &mut batch);
+            (warmup_iterations, warmup_ns, self.calibrate(run_start, &mut batch))
+        };
+        // In an interleaved run, let the other variants calibrate before the first sample.
+        if let Some(turn) = &self.turn {
+            turn.yield_turn();
+        }
 
         on_calibrated();
+        alloc::reset();
 
-        let target_ns = self.measurement_ms as f64 * 1_000_000.0;
-        let iters_per_ns = batch_size as f64 / batch_time_ns;
-        let total_iters = (iters_per_ns * target_ns).ceil() as usize;
-
-        let statistics = Self::measure(timer, f, total_iters);
+        let timed_out_early = self.fixed_iterations.is_none() && self.timed_out(run_start);
+        let (samples_ns, iterations, stop_reason, allocations, counters) = if let Some(iterations) = self.fixed_iterations {
+            let iterations = iterations.max(1);
+            if self.perf_counters {
+                perf::open();
+            }
+            let samples_ns = vec![batch(iterations) / iterations as f64];
+            let allocations = alloc::counts().map(|counts| AllocationStats::per_iteration(counts, iterations));
+            let counters = perf::close().map(|counts| CounterStats::per_iteration(counts, iterations));
+            (samples_ns, iterations, StopReason::IterationCount, allocations, counters)
+        } else if timed_out_early {
+            // Out of time before measuring; the calibration batches are all we have.
+            let samples_ns = calibration_batches.iter().map(|b| b.elapsed_ns / b.iterations as f64).collect();
+            let iterations = calibration_batches.iter().map(|b| b.iterations).sum();
+            (samples_ns, iterations, StopReason::TimedOut, None, None)
+        } else {
+            let calibration_iters: usize = calibration_batches.iter().map(|b| b.iterations).sum();
+            let calibration_ns: f64 = calibration_batches.iter().map(|b| b.elapsed_ns).sum();
+            let target_ns = self.measurement_ms as f64 * 1_000_000.0;
+            let iters_per_ns = calibration_iters as f64 / calibration_ns;
+            let total_iters = (iters_per_ns * target_ns).ceil() as usize;
+            let sample_count = self.sample_count.clamp(1, total_iters.max(1));
+            let iters_per_sample = total_iters
+                .div_ceil(sample_count)
+                .min(self.max_iterations.unwrap_or(usize::MAX))
+                .max(1);
+
+            if self.perf_counters {
+                perf::open();
+            }
+            let (samples_ns, stop_reason) = self.measure(run_start, batch, sample_count, iters_per_sample);
+            let iterations = samples_ns.len() * iters_per_sample;
+            let allocations = alloc::counts().map(|counts| AllocationStats::per_iteration(counts, iterations));
+            let counters = perf::close().map(|counts| CounterStats::per_iteration(counts, iterations));
+            (samples_ns, iterations, stop_reason, allocations, counters)
+        };
+        let (statistics, outliers) = self.summarize(&samples_ns, iterations);
 
         BenchmarkResult {
             id: id.to_string(),
             category: category.to_string(),
             name: name.to_string(),
             simd_variant: simd_variant.to_string(),
+            timer: timer.id().to_string(),
             statistics,
+            outliers,
+            stop_reason,
+            diagnostics: RunDiagnostics {
+                warmup_iterations,
+                warmup_ns,
+                calibration_batches,
+                timed_out: timed_out_early,
+            },
+            throughput: None,
+            allocations,
+            counters,
+            pinned_core: placement.pinned_core(),
+            nice: placement.nice(),
+            setup_mean_ns: None,
+            samples_ns,
+            params: Params::new(),
+            level_agnostic: false,
             timestamp_ms: timer.timestamp_ms(),
         }
     }
 
+    /// Wrap `f` into a closure that times the given number of iterations.
+    fn timed_batch<F: FnMut()>(&self, mut f: F) -> impl FnMut(usize) -> f64 {
+        let timer = &*self.timer;
+        move |iterations| {
+            begin_timed_region();
+            let start = timer.now();
+            for _ in 0..iterations {
+                f();
+            }
+            let elapsed_ns = timer.elapsed_ns(start);
+            end_timed_region();
+            elapsed_ns
+        }
+    }
+
+    /// Record an output of the workload after running it, so that SIMD levels can be checked
+    /// against each other (see `validate`). `elements` is only called when validating.
+    pub fn record_output<I>(&self, name: &str, elements: impl FnOnce() -> I)
+    where
+        I: IntoIterator,
+        I::Item: Debug,
+    {
+        if let Some(outputs) = &self.outputs {
+            outputs.borrow_mut().push(Output::new(name, elements()));
+        }
+    }
+
     /// Run a benchmark and return the result.
     pub fn run<F>(&self, id: &str, category: &str, name: &str, simd_variant: &str, f: F) -> BenchmarkResult
     where
         F: FnMut(),
     {
-        self.run_with_timer(&PlatformTimer::default(), id, category, name, simd_variant, f, || {})
+        self.run_inner(id, category, name, simd_variant, self.timed_batch(f), || {})
     }
 
     /// Run a benchmark with a callback when calibration completes.
@@ -97,98 +573,71 @@ impl BenchRunner {
         F: FnMut(),
         C: FnOnce(),
     {
-        self.run_with_timer(&PlatformTimer::default(), id, category, name, simd_variant, f, on_calibrated)
+        self.run_inner(id, category, name, simd_variant, self.timed_batch(f), on_calibrated)
     }
-}
-
-/// Timer abstraction for platform-independent benchmarking.
-trait Timer {
-    type Instant: Copy;
-
-    fn now(&self) -> Self::Instant;
-    fn elapsed_ns(&self, start: Self::Instant) -> f64;
-    fn timestamp_ms(&self) -> u64;
-}
 
-#[cfg(not(target_arch = "wasm32"))]
-type PlatformTimer = NativeTimer;
-#[cfg(target_arch = "wasm32")]
-type PlatformTimer = WasmTimer;
-
-/// Native timer using std::time.
-#[cfg(not(target_arch = "wasm32"))]
-struct NativeTimer;
-
-#[cfg(not(target_arch = "wasm32"))]
-impl Default for NativeTimer {
-    fn default() -> Self { Self }
-}
-
-#[cfg(not(target_arch = "wasm32"))]
-impl Timer for NativeTimer {
-    type Instant = std::time::Instant;
-
-    fn now(&self) -> Self::Instant {
-        std::time::Instant::now()
-    }
-
-    fn elapsed_ns(&self, start: Self::Instant) -> f64 {
-        start.elapsed().as_nanos() as f64
-    }
-
-    fn timestamp_ms(&self) -> u64 {
-        std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .map(|d| d.as_millis() as u64)
-            .unwrap_or(0)
-    }
-}
-
-/// WASM timer using Performance API.
-/// Works in both Window and Worker contexts.
-#[cfg(target_arch = "wasm32")]
-struct WasmTimer {
-    performance: web_sys::Performance,
-}
-
-// Note that the JS performance timer is likely not super accurate. However,
-// in our case we have a calibration phase to estimate how many iterations are needed
-// to run for a given number of seconds, and then we run the benchmarks all at once
-// for the estimated number of iterations. Therefore, being a few milliseconds off
-// does not matter that much.
-#[cfg(target_arch = "wasm32")]
-impl WasmTimer {
-    fn new() -> Self {
-        use wasm_bindgen::JsCast;
-
-        // Use js_sys::global() which works in both Window and Worker contexts
-        let global = js_sys::global();
-        let performance = js_sys::Reflect::get(&global, &wasm_bindgen::JsValue::from_str("performance"))
-            .expect("no performance on global")
-            .unchecked_into::<web_sys::Performance>();
-
-        Self { performance }
-    }
-}
-
-#[cfg(target_arch = "wasm32")]
-impl Default for WasmTimer {
-    fn default() -> Self { Self::new() }
-}
-
-#[cfg(target_arch = "wasm32")]
-impl Timer for WasmTimer {
-    type Instant = f64; // performance.now() returns milliseconds as f64
+    /// Run a benchmark whose iterations need fresh input, in the style of `iter_batched`.
+    ///
+    /// `setup` produces the input for one iteration and is timed separately; only `routine`
+    /// counts towards the statistics. Inputs are prepared `SETUP_BATCH_SIZE` at a time, and
+    /// outputs are dropped after the timer stops, so neither counts towards the reported
+    /// allocations. The mean setup time per measured iteration is reported as `setup_mean_ns`.
+    pub fn run_batched<S, R, I, O>(
+        &self,
+        id: &str,
+        category: &str,
+        name: &str,
+        simd_variant: &str,
+        mut setup: S,
+        mut routine: R,
+    ) -> BenchmarkResult
+    where
+        S: FnMut() -> I,
+        R: FnMut(I) -> O,
+    {
+        let timer = &*self.timer;
+        let setup_ns = Cell::new(0.0);
+        let setup_iterations = Cell::new(0usize);
+        let mut inputs = Vec::with_capacity(SETUP_BATCH_SIZE);
+        let mut outputs = Vec::with_capacity(SETUP_BATCH_SIZE);
+
+        let batch = |iterations: usize| {
+            let mut elapsed_ns = 0.0;
+            let mut remaining = iterations;
+
+            while remaining > 0 {
+                let chunk = remaining.min(SETUP_BATCH_SIZE);
+
+                let start = timer.now();
+                inputs.extend((0..chunk).map(|_| setup()));
+                setup_ns.set(setup_ns.get() + timer.elapsed_ns(start));
+                setup_iterations.set(setup_iterations.get() + chunk);
+
+                begin_timed_region();
+                let start = timer.now();
+                for input in inputs.drain(..) {
+                    outputs.push(std::hint::black_box(routine(input)));
+                }
+                elapsed_ns += timer.elapsed_ns(start);
+                end_timed_region();
+
+                outputs.clear();
+                remaining -= chunk;
+            }
 
-    fn now(&self) -> Self::Instant {
-        self.performance.now()
-    }
+            elapsed_ns
+        };
 
-    fn elapsed_ns(&self, start: Self::Instant) -> f64 {
-        (self.performance.now() - start) * 1_000_000.0
-    }
+        // Only count setup done during the measurement phase.
+        let on_calibrated = || {
+            setup_ns.set(0.0);
+            setup_iterations.set(0);
+        };
 
-    fn timestamp_ms(&self) -> u64 {
-        self.performance.now() as u64
+        let mut result = self.run_inner(id, category, name, simd_variant, batch, on_calibrated);
+        if setup_iterations.get() > 0 {
+            result.setup_mean_ns = Some(setup_ns.get() / setup_iterations.get() as f64);
+        }
+        result
     }
 }
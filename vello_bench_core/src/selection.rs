@@ -0,0 +1,197 @@
+//! Choosing benchmarks by id pattern, category and tag.
+//!
+//! A selection can be written as a single string, so that every frontend accepts the same
+//! one. It is a whitespace-separated list of terms:
+//!
+//! - `fine/gradient/*`: include ids matching a glob, where `*` matches any run of
+//!   characters (including `/`) and `?` any single character;
+//! - `re:^fine/(fill|strip)/`: include ids matching a regular expression anywhere;
+//! - `!*/many_stops`, `!re:_long$`: exclude ids matching a glob or regular expression;
+//! - `category:fine`: only a category and its subcategories;
+//! - `tag:svg`: only benchmarks with a tag.
+//!
+//! For example, `fine/gradient/* !*/many_stops` selects all gradient benchmarks except
+//! `many_stops`.
+
+use crate::registry::{BenchmarkInfo, get_benchmark_list};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+/// A set of benchmarks, described by filters over their metadata.
+///
+/// A benchmark is selected if it matches at least one include pattern (or there are none),
+/// no exclude pattern, at least one category (or there are none), and all tags.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Selection {
+    /// Glob patterns over ids, or regular expressions prefixed with `re:`.
+    pub include: Vec<String>,
+    /// Patterns over ids, in the same syntax as `include`, that deselect benchmarks.
+    pub exclude: Vec<String>,
+    /// Categories; subcategories are included (e.g., "fine" includes "fine/fill").
+    pub categories: Vec<String>,
+    /// Tags a benchmark must all have.
+    pub tags: Vec<String>,
+}
+
+impl Selection {
+    /// Parse the string form described in the module docs.
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let mut selection = Self::default();
+        for term in spec.split_whitespace() {
+            if let Some(category) = term.strip_prefix("category:") {
+                selection.categories.push(category.to_string());
+            } else if let Some(tag) = term.strip_prefix("tag:") {
+                selection.tags.push(tag.to_string());
+            } else if let Some(pattern) = term.strip_prefix('!') {
+                selection.exclude.push(pattern.to_string());
+            } else {
+                selection.include.push(term.to_string());
+            }
+        }
+
+        // Report invalid regular expressions now rather than when filtering.
+        selection.matcher()?;
+        Ok(selection)
+    }
+
+    /// Whether the selection has no filters, and so selects everything.
+    pub fn is_empty(&self) -> bool {
+        self.include.is_empty() && self.exclude.is_empty() && self.categories.is_empty() && self.tags.is_empty()
+    }
+
+    /// The benchmarks of `benchmarks` that are selected, in their original order. Fails if
+    /// a regular expression is invalid.
+    pub fn filter(&self, benchmarks: &[BenchmarkInfo]) -> Result<Vec<BenchmarkInfo>, String> {
+        let matcher = self.matcher()?;
+        Ok(benchmarks.iter().filter(|b| matcher.matches(b)).cloned().collect())
+    }
+
+    fn matcher(&self) -> Result<Matcher<'_>, String> {
+        let compile = |patterns: &[String]| patterns.iter().map(|p| Pattern::new(p)).collect::<Result<Vec<_>, _>>();
+        Ok(Matcher { selection: self, include: compile(&self.include)?, exclude: compile(&self.exclude)? })
+    }
+}
+
+/// Select from all registered benchmarks.
+pub fn select_benchmarks(selection: &Selection) -> Result<Vec<BenchmarkInfo>, String> {
+    selection.filter(&get_benchmark_list())
+}
+
+/// A selection with its patterns compiled.
+struct Matcher<'a> {
+    selection: &'a Selection,
+    include: Vec<Pattern>,
+    exclude: Vec<Pattern>,
+}
+
+impl Matcher<'_> {
+    fn matches(&self, benchmark: &BenchmarkInfo) -> bool {
+        let selection = self.selection;
+        (self.include.is_empty() || self.include.iter().any(|p| p.matches(&benchmark.id)))
+            && !self.exclude.iter().any(|p| p.matches(&benchmark.id))
+            && (selection.categories.is_empty() || selection.categories.iter().any(|c| in_category(&benchmark.category, c)))
+            && selection.tags.iter().all(|t| benchmark.tags.contains(t))
+    }
+}
+
+fn in_category(category: &str, wanted: &str) -> bool {
+    category.strip_prefix(wanted).is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+}
+
+enum Pattern {
+    Glob(String),
+    Regex(Regex),
+}
+
+impl Pattern {
+    fn new(pattern: &str) -> Result<Self, String> {
+        match pattern.strip_prefix("re:") {
+            Some(regex) => Regex::new(regex).map(Self::Regex).map_err(|e| format!("invalid pattern {pattern:?}: {e}")),
+            None => Ok(Self::Glob(pattern.to_string())),
+        }
+    }
+
+    fn matches(&self, id: &str) -> bool {
+        match self {
+            Self::Glob(glob) => glob_matches(glob.as_bytes(), id.as_bytes()),
+            Self::Regex(regex) => regex.is_match(id),
+        }
+    }
+}
+
+/// Match a whole string against a glob of `*` and `?` wildcards.
+fn glob_matches(glob: &[u8], text: &[u8]) -> bool {
+    // Backtrack to just after the last `*` on a mismatch, letting it swallow one more byte.
+    let (mut g, mut t) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        match glob.get(g) {
+            Some(b'*') => {
+                star = Some((g, t));
+                g += 1;
+            }
+            Some(&c) if c == b'?' || c == text[t] => {
+                g += 1;
+                t += 1;
+            }
+            _ => match star {
+                Some((star_g, star_t)) => {
+                    g = star_g + 1;
+                    t = star_t + 1;
+                    star = Some((star_g, star_t + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+
+    glob[g..].iter().all(|&c| c == b'*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::glob_matches;
+
+    fn matches(glob: &str, text: &str) -> bool {
+        glob_matches(glob.as_bytes(), text.as_bytes())
+    }
+
+    #[test]
+    fn glob_without_wildcards_matches_exactly() {
+        assert!(matches("fine/fill", "fine/fill"));
+        assert!(!matches("fine/fill", "fine/fill_opaque"));
+        assert!(!matches("fine/fill", "fine/fil"));
+        assert!(matches("", ""));
+        assert!(!matches("", "a"));
+    }
+
+    #[test]
+    fn glob_star_matches_any_run() {
+        assert!(matches("*", ""));
+        assert!(matches("*", "fine/fill"));
+        assert!(matches("fine/*", "fine/fill"));
+        assert!(matches("fine/*", "fine/"));
+        assert!(matches("*/fill", "fine/fill"));
+        assert!(matches("f*l*l", "fine/fill"));
+        assert!(matches("**fill", "fine/fill"));
+        assert!(!matches("fine/*", "strip/render"));
+        assert!(!matches("*fill", "fine/fills"));
+    }
+
+    #[test]
+    fn glob_star_backtracks() {
+        assert!(matches("*ab", "aab"));
+        assert!(matches("a*b*c", "abxbyc"));
+        assert!(!matches("a*b*c", "abxbyd"));
+    }
+
+    #[test]
+    fn glob_question_mark_matches_one_byte() {
+        assert!(matches("fine/fil?", "fine/fill"));
+        assert!(!matches("fine/fil?", "fine/fil"));
+        assert!(!matches("?", ""));
+        assert!(matches("?*", "x"));
+    }
+}
@@ -1,3 +1,4 @@
+use crate::error::BenchError;
 use serde::{Deserialize, Serialize};
 use fearless_simd::Level;
 #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
@@ -76,22 +77,13 @@ pub fn level_display_name(level: Level) -> &'static str {
     }
 }
 
-/// Parse a SIMD level from a suffix string (as returned by `level_suffix`).
-/// Falls back to `Level::new()` (best available) if the string is unrecognized.
-#[allow(unsafe_code)]
-pub fn level_from_suffix(s: &str) -> Level {
-    match s {
-        "scalar" => Level::Fallback(Fallback::new()),
-        #[cfg(target_arch = "aarch64")]
-        "neon" => Level::Neon(unsafe { Neon::new_unchecked() }),
-        #[cfg(all(target_arch = "wasm32", target_feature = "simd128"))]
-        "wasm_simd128" => Level::WasmSimd128(fearless_simd::WasmSimd128::new_unchecked()),
-        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
-        "sse42" => Level::Sse4_2(unsafe { Sse4_2::new_unchecked() }),
-        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
-        "avx2" => Level::Avx2(unsafe { Avx2::new_unchecked() }),
-        _ => panic!("unknown SIMD level suffix: {s:?}"),
-    }
+/// Parse a SIMD level from a suffix string (as returned by `level_suffix`). Fails if the
+/// level is unknown or not available on this CPU.
+pub fn level_from_suffix(s: &str) -> Result<Level, BenchError> {
+    available_levels()
+        .into_iter()
+        .find(|level| level_suffix(*level) == s)
+        .ok_or_else(|| BenchError::UnsupportedLevel { level: s.to_string() })
 }
 
 /// Get `SimdLevelInfo` structs for all available levels, suitable for sending to a frontend.
@@ -0,0 +1,258 @@
+//! Summary statistics over benchmark samples.
+
+use rand::prelude::StdRng;
+use rand::{Rng, SeedableRng};
+
+/// Fixed seed so that repeated analyses of the same samples give the same intervals.
+const BOOTSTRAP_SEED: [u8; 32] = [0; 32];
+
+/// Arithmetic mean of `values`.
+pub fn mean(values: &[f64]) -> f64 {
+    if values.is_empty() {
+        return 0.0;
+    }
+
+    values.iter().sum::<f64>() / values.len() as f64
+}
+
+/// Sample standard deviation (with Bessel's correction) of `values`.
+pub fn std_dev(values: &[f64]) -> f64 {
+    if values.len() < 2 {
+        return 0.0;
+    }
+
+    let mean = mean(values);
+    let sum_sq: f64 = values.iter().map(|v| (v - mean) * (v - mean)).sum();
+    (sum_sq / (values.len() - 1) as f64).sqrt()
+}
+
+/// Return a sorted copy of `values`.
+pub fn sorted(values: &[f64]) -> Vec<f64> {
+    let mut sorted = values.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    sorted
+}
+
+/// Percentile `p` (in `0.0..=1.0`) of an already sorted slice, using linear interpolation
+/// between the two closest ranks.
+pub fn percentile(sorted: &[f64], p: f64) -> f64 {
+    match sorted.len() {
+        0 => 0.0,
+        1 => sorted[0],
+        len => {
+            let rank = p.clamp(0.0, 1.0) * (len - 1) as f64;
+            let lower = rank.floor() as usize;
+            let upper = rank.ceil() as usize;
+            let fraction = rank - lower as f64;
+            sorted[lower] + (sorted[upper] - sorted[lower]) * fraction
+        }
+    }
+}
+
+/// Median of an already sorted slice.
+pub fn median(sorted: &[f64]) -> f64 {
+    percentile(sorted, 0.5)
+}
+
+/// Median absolute deviation (unscaled) of an already sorted slice.
+pub fn mad(sorted: &[f64]) -> f64 {
+    let median = median(sorted);
+    let deviations: Vec<f64> = sorted.iter().map(|v| (v - median).abs()).collect();
+    self::median(&self::sorted(&deviations))
+}
+
+/// Bootstrap confidence interval of `statistic` over `values`, as `(lower, upper)`.
+///
+/// Draws `resamples` resamples with replacement and takes the percentiles of the statistic
+/// that enclose the requested confidence `level` (e.g. `0.95`). The resample passed to
+/// `statistic` may be reordered freely.
+pub fn bootstrap<F>(values: &[f64], resamples: usize, level: f64, mut statistic: F) -> (f64, f64)
+where
+    F: FnMut(&mut [f64]) -> f64,
+{
+    if values.len() < 2 || resamples == 0 {
+        let estimate = statistic(&mut values.to_vec());
+        return (estimate, estimate);
+    }
+
+    let mut rng = StdRng::from_seed(BOOTSTRAP_SEED);
+    let mut resample = vec![0.0; values.len()];
+    let mut estimates = Vec::with_capacity(resamples);
+
+    for _ in 0..resamples {
+        for slot in &mut resample {
+            *slot = values[rng.random_range(0..values.len())];
+        }
+        estimates.push(statistic(&mut resample));
+    }
+
+    estimates.sort_by(f64::total_cmp);
+    let tail = (1.0 - level.clamp(0.0, 1.0)) / 2.0;
+    (percentile(&estimates, tail), percentile(&estimates, 1.0 - tail))
+}
+
+/// Tukey fences of a sample set, derived from its interquartile range.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Fences {
+    pub low_severe: f64,
+    pub low_mild: f64,
+    pub high_mild: f64,
+    pub high_severe: f64,
+}
+
+impl Fences {
+    /// Compute the fences of an already sorted slice (1.5 × IQR for mild, 3 × IQR for severe).
+    pub fn new(sorted: &[f64]) -> Self {
+        let q1 = percentile(sorted, 0.25);
+        let q3 = percentile(sorted, 0.75);
+        let iqr = q3 - q1;
+
+        Self {
+            low_severe: q1 - 3.0 * iqr,
+            low_mild: q1 - 1.5 * iqr,
+            high_mild: q3 + 1.5 * iqr,
+            high_severe: q3 + 3.0 * iqr,
+        }
+    }
+
+    /// Whether `value` lies outside the mild fences.
+    pub fn is_outlier(&self, value: f64) -> bool {
+        value < self.low_mild || value > self.high_mild
+    }
+}
+
+/// Two-sided p-value of the Mann-Whitney U test that `a` and `b` come from the same
+/// distribution.
+///
+/// Uses the normal approximation with tie and continuity corrections, which is accurate
+/// enough for the sample counts of a benchmark run. Returns `None` if either side has fewer
+/// than two values.
+pub fn mann_whitney_u(a: &[f64], b: &[f64]) -> Option<f64> {
+    if a.len() < 2 || b.len() < 2 {
+        return None;
+    }
+
+    let mut pooled: Vec<(f64, bool)> = a.iter().map(|&v| (v, true)).chain(b.iter().map(|&v| (v, false))).collect();
+    pooled.sort_by(|x, y| x.0.total_cmp(&y.0));
+
+    let mut rank_sum_a = 0.0;
+    let mut tie_term = 0.0;
+    let mut start = 0;
+    while start < pooled.len() {
+        let end = start + pooled[start..].iter().take_while(|(v, _)| *v == pooled[start].0).count();
+        // Tied values share the average of ranks `start + 1..=end`.
+        let rank = (start + 1 + end) as f64 / 2.0;
+        let from_a = pooled[start..end].iter().filter(|(_, in_a)| *in_a).count();
+        rank_sum_a += rank * from_a as f64;
+        let ties = (end - start) as f64;
+        tie_term += ties * ties * ties - ties;
+        start = end;
+    }
+
+    let (n1, n2) = (a.len() as f64, b.len() as f64);
+    let n = n1 + n2;
+    let u = rank_sum_a - n1 * (n1 + 1.0) / 2.0;
+    let variance = n1 * n2 / 12.0 * ((n + 1.0) - tie_term / (n * (n - 1.0)));
+    if variance <= 0.0 {
+        // All values are equal.
+        return Some(1.0);
+    }
+
+    let z = ((u - n1 * n2 / 2.0).abs() - 0.5).max(0.0) / variance.sqrt();
+    Some(erfc(z / std::f64::consts::SQRT_2).min(1.0))
+}
+
+/// Complementary error function, with a fractional error below 1.2e-7 (Numerical Recipes'
+/// Chebyshev approximation).
+fn erfc(x: f64) -> f64 {
+    let z = x.abs();
+    let t = 1.0 / (1.0 + 0.5 * z);
+    let poly = -1.265_512_23
+        + t * (1.000_023_68
+            + t * (0.374_091_96
+                + t * (0.096_784_18
+                    + t * (-0.186_288_06
+                        + t * (0.278_868_07
+                            + t * (-1.135_203_98 + t * (1.488_515_87 + t * (-0.822_152_23 + t * 0.170_872_77))))))));
+    let r = t * (-z * z + poly).exp();
+    if x >= 0.0 { r } else { 2.0 - r }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn percentile_interpolates_between_ranks() {
+        let sorted = [1.0, 2.0, 3.0, 4.0, 5.0];
+        assert_eq!(percentile(&sorted, 0.0), 1.0);
+        assert_eq!(percentile(&sorted, 0.5), 3.0);
+        assert_eq!(percentile(&sorted, 1.0), 5.0);
+        assert_eq!(percentile(&sorted, 0.1), 1.4);
+        assert_eq!(percentile(&[10.0, 20.0], 0.25), 12.5);
+    }
+
+    #[test]
+    fn percentile_handles_short_slices_and_out_of_range_p() {
+        assert_eq!(percentile(&[], 0.5), 0.0);
+        assert_eq!(percentile(&[7.0], 0.9), 7.0);
+        assert_eq!(percentile(&[1.0, 2.0, 3.0], -1.0), 1.0);
+        assert_eq!(percentile(&[1.0, 2.0, 3.0], 2.0), 3.0);
+    }
+
+    #[test]
+    fn bootstrap_interval_encloses_the_estimate() {
+        let values: Vec<f64> = (1..=50).map(f64::from).collect();
+        let (lower, upper) = bootstrap(&values, 1000, 0.95, |v| mean(v));
+        assert!(lower < 25.5 && 25.5 < upper, "({lower}, {upper})");
+        assert!(upper - lower < 15.0, "({lower}, {upper})");
+    }
+
+    #[test]
+    fn bootstrap_is_deterministic() {
+        let values = [3.0, 1.0, 4.0, 1.0, 5.0, 9.0, 2.0, 6.0];
+        let median = |v: &mut [f64]| {
+            v.sort_by(f64::total_cmp);
+            self::median(v)
+        };
+        assert_eq!(bootstrap(&values, 200, 0.9, median), bootstrap(&values, 200, 0.9, median));
+    }
+
+    #[test]
+    fn bootstrap_without_resampling_returns_the_estimate() {
+        assert_eq!(bootstrap(&[4.0], 100, 0.95, |v| mean(v)), (4.0, 4.0));
+        assert_eq!(bootstrap(&[1.0, 3.0], 0, 0.95, |v| mean(v)), (2.0, 2.0));
+    }
+
+    #[test]
+    fn mann_whitney_u_separates_shifted_samples() {
+        let a: Vec<f64> = (0..20).map(f64::from).collect();
+        let b: Vec<f64> = (100..120).map(f64::from).collect();
+        let p = mann_whitney_u(&a, &b).unwrap();
+        assert!(p < 1e-6, "{p}");
+        assert_eq!(mann_whitney_u(&b, &a), Some(p));
+    }
+
+    #[test]
+    fn mann_whitney_u_accepts_interleaved_samples() {
+        let a: Vec<f64> = (0..20).map(|i| f64::from(2 * i)).collect();
+        let b: Vec<f64> = (0..20).map(|i| f64::from(2 * i + 1)).collect();
+        let p = mann_whitney_u(&a, &b).unwrap();
+        assert!(p > 0.5, "{p}");
+    }
+
+    #[test]
+    fn mann_whitney_u_edge_cases() {
+        assert_eq!(mann_whitney_u(&[1.0], &[2.0, 3.0]), None);
+        assert_eq!(mann_whitney_u(&[1.0, 2.0], &[]), None);
+        assert_eq!(mann_whitney_u(&[5.0; 4], &[5.0; 6]), Some(1.0));
+    }
+
+    #[test]
+    fn erfc_matches_reference_values() {
+        for (x, expected) in [(0.0, 1.0), (0.5, 0.479_500_122), (1.0, 0.157_299_207), (2.0, 0.004_677_735)] {
+            assert!((erfc(x) - expected).abs() <= 1.2e-7 * expected, "erfc({x}) = {}", erfc(x));
+            assert!((erfc(-x) - (2.0 - expected)).abs() <= 1e-7, "erfc({}) = {}", -x, erfc(-x));
+        }
+    }
+}
@@ -0,0 +1,252 @@
+//! Clocks used to time benchmark iterations.
+
+use serde::{Deserialize, Serialize};
+use std::fmt::Debug;
+use std::sync::Arc;
+
+/// Timer abstraction for platform-independent benchmarking.
+///
+/// Readings are nanoseconds relative to an arbitrary origin that is fixed for the lifetime
+/// of the timer, so only differences between readings are meaningful. Timers are shared
+/// with the threads of an interleaved run (see `interleave`).
+pub trait Timer: Debug + Send + Sync {
+    /// Short identifier recorded in results (e.g., "wall", "thread_cpu").
+    fn id(&self) -> &'static str;
+
+    /// Current reading in nanoseconds.
+    fn now(&self) -> f64;
+
+    /// Nanoseconds elapsed since an earlier reading.
+    fn elapsed_ns(&self, start: f64) -> f64 {
+        self.now() - start
+    }
+
+    /// Wall-clock timestamp (milliseconds since epoch) to attach to results.
+    fn timestamp_ms(&self) -> u64;
+}
+
+/// Information about a timer, suitable for serialization to frontends.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimerInfo {
+    pub id: String,
+    pub name: String,
+}
+
+/// The default timer of the current platform.
+pub fn platform_timer() -> Arc<dyn Timer> {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        Arc::new(NativeTimer::default())
+    }
+    #[cfg(target_arch = "wasm32")]
+    {
+        Arc::new(WasmTimer::default())
+    }
+}
+
+/// Create a timer from its id (as returned by `Timer::id`), if it exists on this platform.
+pub fn timer_from_id(id: &str) -> Option<Arc<dyn Timer>> {
+    match id {
+        #[cfg(not(target_arch = "wasm32"))]
+        "wall" => Some(Arc::new(NativeTimer::default())),
+        #[cfg(target_os = "linux")]
+        "thread_cpu" => Some(Arc::new(ThreadCpuTimer::default())),
+        #[cfg(target_os = "linux")]
+        "monotonic_raw" => Some(Arc::new(MonotonicRawTimer::default())),
+        #[cfg(target_arch = "wasm32")]
+        "performance" => Some(Arc::new(WasmTimer::default())),
+        _ => None,
+    }
+}
+
+/// Get `TimerInfo` structs for all timers available on this platform, default first.
+pub fn available_timer_infos() -> Vec<TimerInfo> {
+    let timers: &[(&str, &str)] = &[
+        #[cfg(not(target_arch = "wasm32"))]
+        ("wall", "Wall clock"),
+        #[cfg(target_os = "linux")]
+        ("thread_cpu", "Thread CPU time"),
+        #[cfg(target_os = "linux")]
+        ("monotonic_raw", "Monotonic raw"),
+        #[cfg(target_arch = "wasm32")]
+        ("performance", "performance.now()"),
+    ];
+
+    timers
+        .iter()
+        .map(|(id, name)| TimerInfo {
+            id: (*id).to_string(),
+            name: (*name).to_string(),
+        })
+        .collect()
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn system_timestamp_ms() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// Native timer using std::time.
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Debug)]
+pub struct NativeTimer {
+    origin: std::time::Instant,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Default for NativeTimer {
+    fn default() -> Self {
+        Self { origin: std::time::Instant::now() }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Timer for NativeTimer {
+    fn id(&self) -> &'static str {
+        "wall"
+    }
+
+    fn now(&self) -> f64 {
+        self.origin.elapsed().as_nanos() as f64
+    }
+
+    fn timestamp_ms(&self) -> u64 {
+        system_timestamp_ms()
+    }
+}
+
+/// Read a POSIX clock in nanoseconds.
+#[cfg(target_os = "linux")]
+#[allow(unsafe_code)]
+fn clock_ns(clock: libc::clockid_t) -> i128 {
+    let mut ts = libc::timespec { tv_sec: 0, tv_nsec: 0 };
+    // SAFETY: `ts` is a valid, writable `timespec` and `clock` is a clock id supported by Linux.
+    let ret = unsafe { libc::clock_gettime(clock, &mut ts) };
+    assert_eq!(ret, 0, "clock_gettime failed for clock {clock}");
+    i128::from(ts.tv_sec) * 1_000_000_000 + i128::from(ts.tv_nsec)
+}
+
+/// Timer measuring the CPU time consumed by the calling thread (`CLOCK_THREAD_CPUTIME_ID`).
+///
+/// Time the thread spends preempted or blocked is not counted, which filters out noise
+/// from other processes on shared machines. Readings are only meaningful on the thread
+/// that takes them.
+#[cfg(target_os = "linux")]
+#[derive(Debug)]
+pub struct ThreadCpuTimer {
+    origin: i128,
+}
+
+#[cfg(target_os = "linux")]
+impl Default for ThreadCpuTimer {
+    fn default() -> Self {
+        Self { origin: clock_ns(libc::CLOCK_THREAD_CPUTIME_ID) }
+    }
+}
+
+#[cfg(target_os = "linux")]
+impl Timer for ThreadCpuTimer {
+    fn id(&self) -> &'static str {
+        "thread_cpu"
+    }
+
+    fn now(&self) -> f64 {
+        (clock_ns(libc::CLOCK_THREAD_CPUTIME_ID) - self.origin) as f64
+    }
+
+    fn timestamp_ms(&self) -> u64 {
+        system_timestamp_ms()
+    }
+}
+
+/// Timer using `CLOCK_MONOTONIC_RAW`, which is not subject to NTP frequency adjustments.
+#[cfg(target_os = "linux")]
+#[derive(Debug)]
+pub struct MonotonicRawTimer {
+    origin: i128,
+}
+
+#[cfg(target_os = "linux")]
+impl Default for MonotonicRawTimer {
+    fn default() -> Self {
+        Self { origin: clock_ns(libc::CLOCK_MONOTONIC_RAW) }
+    }
+}
+
+#[cfg(target_os = "linux")]
+impl Timer for MonotonicRawTimer {
+    fn id(&self) -> &'static str {
+        "monotonic_raw"
+    }
+
+    fn now(&self) -> f64 {
+        (clock_ns(libc::CLOCK_MONOTONIC_RAW) - self.origin) as f64
+    }
+
+    fn timestamp_ms(&self) -> u64 {
+        system_timestamp_ms()
+    }
+}
+
+/// WASM timer using Performance API.
+/// Works in both Window and Worker contexts.
+#[cfg(target_arch = "wasm32")]
+#[derive(Debug)]
+pub struct WasmTimer {
+    performance: web_sys::Performance,
+}
+
+// Note that the JS performance timer is likely not super accurate. However,
+// in our case we have a calibration phase to estimate how many iterations are needed
+// to run for a given number of seconds, and each timed sample then runs for a fraction
+// of the measurement time. Therefore, being a fraction of a millisecond off
+// does not matter that much.
+#[cfg(target_arch = "wasm32")]
+impl WasmTimer {
+    fn new() -> Self {
+        use wasm_bindgen::JsCast;
+
+        // Use js_sys::global() which works in both Window and Worker contexts
+        let global = js_sys::global();
+        let performance = js_sys::Reflect::get(&global, &wasm_bindgen::JsValue::from_str("performance"))
+            .expect("no performance on global")
+            .unchecked_into::<web_sys::Performance>();
+
+        Self { performance }
+    }
+}
+
+// SAFETY: WASM builds are single-threaded, so the `Performance` handle is never accessed
+// from another thread.
+#[cfg(all(target_arch = "wasm32", not(target_feature = "atomics")))]
+#[allow(unsafe_code)]
+unsafe impl Send for WasmTimer {}
+
+// SAFETY: As for `Send`.
+#[cfg(all(target_arch = "wasm32", not(target_feature = "atomics")))]
+#[allow(unsafe_code)]
+unsafe impl Sync for WasmTimer {}
+
+#[cfg(target_arch = "wasm32")]
+impl Default for WasmTimer {
+    fn default() -> Self { Self::new() }
+}
+
+#[cfg(target_arch = "wasm32")]
+impl Timer for WasmTimer {
+    fn id(&self) -> &'static str {
+        "performance"
+    }
+
+    fn now(&self) -> f64 {
+        // performance.now() returns milliseconds as f64
+        self.performance.now() * 1_000_000.0
+    }
+
+    fn timestamp_ms(&self) -> u64 {
+        self.performance.now() as u64
+    }
+}
@@ -0,0 +1,143 @@
+//! Checking that every SIMD level computes the same outputs.
+//!
+//! Speed alone can't tell a fast SIMD path from a wrong one. Here, a benchmark runs its
+//! workload exactly once per available level, recording what it produced (see
+//! `BenchRunner::record_output`): the pixels of the `Fine` scratch buffer, the flattened
+//! lines, the tiles, or the strip and alpha buffers. Every level is then compared against
+//! scalar, element by element.
+
+use crate::error::BenchError;
+use crate::params::Params;
+use crate::registry::{get_benchmark_info, run_benchmark_by_id};
+use crate::runner::BenchRunner;
+use crate::simd::{available_levels, level_suffix};
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::fmt::Debug;
+
+/// An output of a workload, with its elements formatted for comparison and display.
+#[derive(Debug, Clone)]
+pub(crate) struct Output {
+    name: String,
+    elements: Vec<String>,
+}
+
+impl Output {
+    pub(crate) fn new<I>(name: &str, elements: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Debug,
+    {
+        Self { name: name.to_string(), elements: elements.into_iter().map(|e| format!("{e:?}")).collect() }
+    }
+}
+
+/// First difference between an output of a level and that of the reference level.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Mismatch {
+    /// Name of the output (e.g., "tiles").
+    pub output: String,
+    /// Index of the first differing element.
+    pub index: usize,
+    /// Element of the reference level, or `None` if its output is shorter.
+    pub expected: Option<String>,
+    /// Element of the checked level, or `None` if its output is shorter.
+    pub actual: Option<String>,
+    /// Number of differing elements in this output, counting missing ones.
+    pub count: usize,
+}
+
+/// Outcome for one SIMD level.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LevelValidation {
+    /// SIMD level suffix (e.g., "avx2").
+    pub simd_level: String,
+    /// First difference to the reference level, if any.
+    pub mismatch: Option<Mismatch>,
+}
+
+/// Outcome of validating a benchmark across SIMD levels.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Validation {
+    /// Full benchmark ID (e.g., "fine/fill/opaque_short").
+    pub id: String,
+    /// Level the others are compared against ("scalar").
+    pub reference_level: String,
+    /// Names of the recorded outputs; empty if the benchmark records none.
+    pub outputs: Vec<String>,
+    /// Whether the benchmark ignores the SIMD level, so no other level was checked.
+    #[serde(default)]
+    pub level_agnostic: bool,
+    /// Every other available level, unless the benchmark is level-agnostic.
+    pub levels: Vec<LevelValidation>,
+}
+
+impl Validation {
+    /// Whether all levels matched the reference.
+    pub fn passed(&self) -> bool {
+        self.levels.iter().all(|l| l.mismatch.is_none())
+    }
+}
+
+/// Run a benchmark once per available SIMD level and compare the outputs of each level
+/// against scalar. Level-agnostic benchmarks only run at scalar.
+pub fn validate_benchmark(id: &str, params: &Params) -> Result<Validation, BenchError> {
+    let level_agnostic = get_benchmark_info(id)?.level_agnostic;
+    let mut levels = available_levels();
+    // Levels are ordered from best to worst, so scalar comes last.
+    let reference_level = levels.pop().expect("scalar is always available");
+    let reference = run_once(id, params, reference_level)?;
+    if level_agnostic {
+        levels.clear();
+    }
+
+    let levels = levels
+        .into_iter()
+        .map(|level| {
+            let outputs = run_once(id, params, level)?;
+            Ok(LevelValidation { simd_level: level_suffix(level).to_string(), mismatch: first_mismatch(&reference, &outputs) })
+        })
+        .collect::<Result<_, BenchError>>()?;
+
+    Ok(Validation {
+        id: id.to_string(),
+        reference_level: level_suffix(reference_level).to_string(),
+        outputs: reference.iter().map(|o| o.name.clone()).collect(),
+        level_agnostic,
+        levels,
+    })
+}
+
+/// Run a single iteration and return the recorded outputs.
+fn run_once(id: &str, params: &Params, level: fearless_simd::Level) -> Result<Vec<Output>, BenchError> {
+    let mut runner = BenchRunner::new(0, 0);
+    runner.warmup_ms = 0;
+    runner.fixed_iterations = Some(1);
+    runner.bootstrap_resamples = 0;
+    runner.timeout_ms = None;
+    runner.outputs = Some(RefCell::default());
+
+    run_benchmark_by_id(&runner, id, level, params)?;
+    Ok(runner.outputs.take().unwrap_or_default().into_inner())
+}
+
+fn first_mismatch(expected: &[Output], actual: &[Output]) -> Option<Mismatch> {
+    let outputs = expected.len().max(actual.len());
+    (0..outputs).find_map(|i| {
+        let (expected, actual) = (expected.get(i), actual.get(i));
+        let name = expected.or(actual).map(|o| o.name.clone()).unwrap_or_default();
+        let expected = expected.map_or(&[][..], |o| &o.elements);
+        let actual = actual.map_or(&[][..], |o| &o.elements);
+
+        let len = expected.len().max(actual.len());
+        let differs = |j: &usize| expected.get(*j) != actual.get(*j);
+        let index = (0..len).find(differs)?;
+        Some(Mismatch {
+            output: name,
+            index,
+            expected: expected.get(index).cloned(),
+            actual: actual.get(index).cloned(),
+            count: (index..len).filter(differs).count(),
+        })
+    })
+}
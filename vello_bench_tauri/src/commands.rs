@@ -5,8 +5,10 @@ use std::path::PathBuf;
 use std::sync::LazyLock;
 use tokio::sync::Mutex;
 use vello_bench_core::{
-    BenchRunner, BenchmarkInfo, BenchmarkResult, SimdLevelInfo,
-    available_level_infos, level_from_suffix,
+    BenchRunner, BenchmarkInfo, BenchmarkResult, CompareOptions, InterleavedResult, LevelMatrix,
+    Params, PreflightReport, ResultSet, RunEnvironment, RunnerOptions, Selection, SetComparison,
+    SimdLevelInfo, TimerInfo, Validation, Variant, available_level_infos, available_timer_infos,
+    level_from_suffix,
 };
 
 /// Mutex to ensure only one benchmark runs at a time.
@@ -18,12 +20,48 @@ pub fn list_benchmarks() -> Vec<BenchmarkInfo> {
     vello_bench_core::get_benchmark_list()
 }
 
+/// List the benchmarks matched by a selection string (see `vello_bench_core::selection`).
+#[tauri::command]
+pub fn select_benchmarks(selection: String) -> Result<Vec<BenchmarkInfo>, String> {
+    let selection = Selection::parse(&selection)?;
+    vello_bench_core::select_benchmarks(&selection)
+}
+
 /// Get available SIMD levels.
 #[tauri::command]
 pub fn get_simd_levels() -> Vec<SimdLevelInfo> {
     available_level_infos()
 }
 
+/// Get available timers.
+#[tauri::command]
+pub fn get_timers() -> Vec<TimerInfo> {
+    available_timer_infos()
+}
+
+/// Describe the machine, build and the runner that `run_benchmark` would use with these
+/// settings, with the noise sources found by the preflight checks.
+#[tauri::command]
+pub fn get_environment(
+    calibration_ms: u64,
+    measurement_ms: u64,
+    options: Option<RunnerOptions>,
+) -> RunEnvironment {
+    let runner = BenchRunner::new(calibration_ms, measurement_ms)
+        .with_options(&options.unwrap_or_default());
+    RunEnvironment::capture(&runner).with_preflight()
+}
+
+/// Check the system for sources of timing noise. In strict mode, any warning is an error.
+#[tauri::command]
+pub fn preflight_checks(strict: bool) -> Result<PreflightReport, String> {
+    let report = vello_bench_core::preflight::run_checks();
+    if strict {
+        report.require_quiet().map_err(|e| e.to_string())?;
+    }
+    Ok(report)
+}
+
 /// Run a single benchmark (async, runs in background thread).
 #[tauri::command]
 pub async fn run_benchmark(
@@ -31,19 +69,86 @@ pub async fn run_benchmark(
     simd_level: String,
     calibration_ms: u64,
     measurement_ms: u64,
-) -> Option<BenchmarkResult> {
+    options: Option<RunnerOptions>,
+    params: Option<Params>,
+) -> Result<BenchmarkResult, String> {
     // Acquire lock to ensure only one benchmark runs at a time
     let _guard = BENCHMARK_LOCK.lock().await;
 
     // Run the benchmark in a blocking thread to not block the async runtime
     tokio::task::spawn_blocking(move || {
-        let level = level_from_suffix(&simd_level);
-        let runner = BenchRunner::new(calibration_ms, measurement_ms);
-        vello_bench_core::run_benchmark_by_id(&runner, &id, level)
+        let level = level_from_suffix(&simd_level)?;
+        let runner = BenchRunner::new(calibration_ms, measurement_ms)
+            .with_options(&options.unwrap_or_default());
+        vello_bench_core::run_benchmark_by_id(&runner, &id, level, &params.unwrap_or_default())
     })
     .await
-    .ok()
-    .flatten()
+    .map_err(|e| format!("benchmark panicked: {e}"))?
+    .map_err(|e| e.to_string())
+}
+
+/// Run several variants with interleaved measurement samples.
+#[tauri::command]
+pub async fn run_interleaved(
+    variants: Vec<Variant>,
+    calibration_ms: u64,
+    measurement_ms: u64,
+    options: Option<RunnerOptions>,
+) -> Result<InterleavedResult, String> {
+    let _guard = BENCHMARK_LOCK.lock().await;
+
+    tokio::task::spawn_blocking(move || {
+        let runner = BenchRunner::new(calibration_ms, measurement_ms)
+            .with_options(&options.unwrap_or_default());
+        vello_bench_core::run_interleaved(&runner, &variants)
+    })
+    .await
+    .map_err(|e| format!("benchmark panicked: {e}"))?
+    .map_err(|e| e.to_string())
+}
+
+/// Run the selected benchmarks at every SIMD level, with speedups over scalar and the best
+/// level.
+#[tauri::command]
+pub async fn run_level_matrix(
+    selection: Selection,
+    calibration_ms: u64,
+    measurement_ms: u64,
+    options: Option<RunnerOptions>,
+    params: Option<Params>,
+) -> Result<LevelMatrix, String> {
+    let _guard = BENCHMARK_LOCK.lock().await;
+
+    tokio::task::spawn_blocking(move || {
+        let runner = BenchRunner::new(calibration_ms, measurement_ms)
+            .with_options(&options.unwrap_or_default());
+        vello_bench_core::run_level_matrix(&runner, &selection, &params.unwrap_or_default())
+    })
+    .await
+    .map_err(|e| format!("benchmark panicked: {e}"))?
+    .map_err(|e| e.to_string())
+}
+
+/// Check that every SIMD level computes the same outputs as scalar.
+#[tauri::command]
+pub async fn validate_benchmark(id: String, params: Option<Params>) -> Result<Validation, String> {
+    let _guard = BENCHMARK_LOCK.lock().await;
+
+    tokio::task::spawn_blocking(move || vello_bench_core::validate_benchmark(&id, &params.unwrap_or_default()))
+        .await
+        .map_err(|e| format!("benchmark panicked: {e}"))?
+        .map_err(|e| e.to_string())
+}
+
+/// Compare results against a baseline, such as a loaded reference, listing the results
+/// without a matching baseline.
+#[tauri::command]
+pub fn compare_results(
+    baseline: Vec<BenchmarkResult>,
+    current: Vec<BenchmarkResult>,
+    options: Option<CompareOptions>,
+) -> SetComparison {
+    vello_bench_core::compare_sets(&baseline, &current, &options.unwrap_or_default())
 }
 
 /// Get the directory for storing reference files.
@@ -63,9 +168,13 @@ pub struct ReferenceInfo {
     pub benchmark_count: usize,
 }
 
-/// Save benchmark results as a named reference.
+/// Save benchmark results, and the environment they were measured in, as a named reference.
 #[tauri::command]
-pub fn save_reference(name: String, results: Vec<BenchmarkResult>) -> Result<(), String> {
+pub fn save_reference(
+    name: String,
+    results: Vec<BenchmarkResult>,
+    environment: Option<RunEnvironment>,
+) -> Result<(), String> {
     let dir = get_references_dir();
 
     fs::create_dir_all(&dir).map_err(|e| format!("Failed to create references directory: {e}"))?;
@@ -88,7 +197,7 @@ pub fn save_reference(name: String, results: Vec<BenchmarkResult>) -> Result<(),
 
     let file_path = dir.join(format!("{safe_name}.json"));
 
-    let json = serde_json::to_string_pretty(&results)
+    let json = serde_json::to_string_pretty(&ResultSet { environment, results })
         .map_err(|e| format!("Failed to serialize results: {e}"))?;
 
     fs::write(&file_path, json).map_err(|e| format!("Failed to write reference file: {e}"))?;
@@ -129,8 +238,8 @@ pub fn list_references() -> Vec<ReferenceInfo> {
             // Try to read and parse to get benchmark count
             let benchmark_count = fs::read_to_string(&path)
                 .ok()
-                .and_then(|content| serde_json::from_str::<Vec<BenchmarkResult>>(&content).ok())
-                .map(|results| results.len())
+                .and_then(|content| ResultSet::from_json(&content).ok())
+                .map(|set| set.results.len())
                 .unwrap_or(0);
 
             references.push(ReferenceInfo {
@@ -148,14 +257,14 @@ pub fn list_references() -> Vec<ReferenceInfo> {
 
 /// Load a reference file by name.
 #[tauri::command]
-pub fn load_reference(name: String) -> Result<Vec<BenchmarkResult>, String> {
+pub fn load_reference(name: String) -> Result<ResultSet, String> {
     let dir = get_references_dir();
     let file_path = dir.join(format!("{name}.json"));
 
     let content =
         fs::read_to_string(&file_path).map_err(|e| format!("Failed to read reference file: {e}"))?;
 
-    serde_json::from_str(&content).map_err(|e| format!("Failed to parse reference file: {e}"))
+    ResultSet::from_json(&content).map_err(|e| format!("Failed to parse reference file: {e}"))
 }
 
 /// Delete a reference file by name.
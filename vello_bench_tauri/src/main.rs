@@ -11,8 +11,16 @@ fn main() {
     tauri::Builder::default()
         .invoke_handler(tauri::generate_handler![
             commands::list_benchmarks,
+            commands::select_benchmarks,
             commands::get_simd_levels,
+            commands::get_timers,
+            commands::get_environment,
+            commands::preflight_checks,
             commands::run_benchmark,
+            commands::run_interleaved,
+            commands::run_level_matrix,
+            commands::validate_benchmark,
+            commands::compare_results,
             commands::save_reference,
             commands::list_references,
             commands::load_reference,
@@ -2,7 +2,12 @@
 
 #![allow(missing_docs, reason = "Not needed for benchmarks")]
 
-use vello_bench_core::{BenchRunner, available_level_infos};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use vello_bench_core::{
+    BenchError, BenchRunner, BenchmarkResult, CompareOptions, Params, RunEnvironment, RunnerOptions, Selection,
+    available_level_infos, available_timer_infos, level_from_suffix,
+};
 use wasm_bindgen::prelude::*;
 
 /// Initialize the WASM module.
@@ -15,14 +20,29 @@ pub fn init() {
 #[wasm_bindgen]
 pub fn list_benchmarks() -> JsValue {
     let benchmarks = vello_bench_core::get_benchmark_list();
-    serde_wasm_bindgen::to_value(&benchmarks).unwrap()
+    to_js(&benchmarks)
+}
+
+/// List the benchmarks matched by a selection string (see `vello_bench_core::selection`).
+#[wasm_bindgen]
+pub fn select_benchmarks(selection: &str) -> Result<JsValue, JsValue> {
+    let benchmarks = Selection::parse(selection)
+        .and_then(|s| vello_bench_core::select_benchmarks(&s))
+        .map_err(|e| JsValue::from_str(&e))?;
+    Ok(to_js(&benchmarks))
 }
 
 /// Get available SIMD levels for this platform.
 #[wasm_bindgen]
 pub fn get_simd_levels() -> JsValue {
     let level_info = available_level_infos();
-    serde_wasm_bindgen::to_value(&level_info).unwrap()
+    to_js(&level_info)
+}
+
+/// Get available timers for this platform.
+#[wasm_bindgen]
+pub fn get_timers() -> JsValue {
+    to_js(&available_timer_infos())
 }
 
 /// Check if SIMD128 is available.
@@ -38,17 +58,111 @@ pub fn has_simd128() -> bool {
     }
 }
 
-/// Run a single benchmark by ID.
+/// Serialize a return value as plain JavaScript objects and arrays (maps such as `Params`
+/// become objects rather than `Map`s), so the UI can index and `JSON.stringify` it.
+fn to_js<T: Serialize + ?Sized>(value: &T) -> JsValue {
+    value.serialize(&serde_wasm_bindgen::Serializer::json_compatible()).unwrap()
+}
+
+/// Convert an error into the value thrown to JavaScript.
+fn to_js_error(error: BenchError) -> JsValue {
+    JsValue::from_str(&error.to_string())
+}
+
+/// Deserialize an argument, naming it as `what` in the error if it is malformed.
+fn from_js<T: DeserializeOwned>(value: JsValue, what: &str) -> Result<T, JsValue> {
+    serde_wasm_bindgen::from_value(value)
+        .map_err(|e| to_js_error(BenchError::InvalidInput { what: what.to_string(), message: e.to_string() }))
+}
+
+/// Deserialize an optional argument, using the default if it is `undefined` or `null`.
+fn optional_from_js<T: DeserializeOwned + Default>(value: JsValue, what: &str) -> Result<T, JsValue> {
+    if value.is_undefined() || value.is_null() { Ok(T::default()) } else { from_js(value, what) }
+}
+
+/// Deserialize an optional object of parameter overrides.
+fn parse_params(params: JsValue) -> Result<Params, JsValue> {
+    optional_from_js(params, "params")
+}
+
+/// Build the runner for the given settings.
+///
+/// `options` is an optional `RunnerOptions` object overriding the runner defaults.
+fn runner(calibration_ms: u32, measurement_ms: u32, options: JsValue) -> Result<BenchRunner, JsValue> {
+    let options: RunnerOptions = optional_from_js(options, "runner options")?;
+    Ok(BenchRunner::new(calibration_ms.into(), measurement_ms.into()).with_options(&options))
+}
+
+/// Describe the browser, build and the runner that `run_benchmark` would use with these
+/// settings.
 #[wasm_bindgen]
-pub fn run_benchmark(id: &str, calibration_ms: u32, measurement_ms: u32) -> JsValue {
-    use fearless_simd::Level;
+pub fn get_environment(calibration_ms: u32, measurement_ms: u32, options: JsValue) -> Result<JsValue, JsValue> {
+    let environment = RunEnvironment::capture(&runner(calibration_ms, measurement_ms, options)?);
+    Ok(to_js(&environment))
+}
 
-    let runner = BenchRunner::new(calibration_ms.into(), measurement_ms.into());
-    let level = Level::new();
+/// Run a single benchmark by ID at the SIMD level with the given suffix (one of the ids
+/// from `get_simd_levels`). Fails with the error message if it couldn't be run.
+///
+/// `options` is an optional `RunnerOptions` object overriding the runner defaults, and
+/// `params` an optional object of parameter overrides.
+#[wasm_bindgen]
+pub fn run_benchmark(
+    id: &str,
+    simd_level: &str,
+    calibration_ms: u32,
+    measurement_ms: u32,
+    options: JsValue,
+    params: JsValue,
+) -> Result<JsValue, JsValue> {
+    let level = level_from_suffix(simd_level).map_err(to_js_error)?;
+    let runner = runner(calibration_ms, measurement_ms, options)?;
+    let params = parse_params(params)?;
 
-    match vello_bench_core::run_benchmark_by_id(&runner, id, level) {
-        Some(result) => serde_wasm_bindgen::to_value(&result).unwrap(),
-        None => JsValue::NULL,
-    }
+    let result = vello_bench_core::run_benchmark_by_id(&runner, id, level, &params).map_err(to_js_error)?;
+    Ok(to_js(&result))
+}
+
+/// Run the selected benchmarks at every SIMD level, with speedups over scalar and the best
+/// level.
+///
+/// `selection` is a `Selection` object; `options` and `params` are as for `run_benchmark`.
+#[wasm_bindgen]
+pub fn run_level_matrix(
+    selection: JsValue,
+    calibration_ms: u32,
+    measurement_ms: u32,
+    options: JsValue,
+    params: JsValue,
+) -> Result<JsValue, JsValue> {
+    let selection: Selection = from_js(selection, "selection")?;
+    let runner = runner(calibration_ms, measurement_ms, options)?;
+    let params = parse_params(params)?;
+    let matrix = vello_bench_core::run_level_matrix(&runner, &selection, &params).map_err(to_js_error)?;
+    Ok(to_js(&matrix))
 }
 
+/// Check that every SIMD level computes the same outputs as scalar. Fails with the error
+/// message if the benchmark couldn't be run.
+///
+/// `params` is an optional object of parameter overrides.
+#[wasm_bindgen]
+pub fn validate_benchmark(id: &str, params: JsValue) -> Result<JsValue, JsValue> {
+    let params = parse_params(params)?;
+    let validation = vello_bench_core::validate_benchmark(id, &params).map_err(to_js_error)?;
+    Ok(to_js(&validation))
+}
+
+/// Compare results against a baseline, such as a loaded reference, returning a
+/// `SetComparison`.
+///
+/// `baseline` and `current` are arrays of `BenchmarkResult`; `options` is an optional
+/// `CompareOptions` object.
+#[wasm_bindgen]
+pub fn compare_results(baseline: JsValue, current: JsValue, options: JsValue) -> Result<JsValue, JsValue> {
+    let baseline: Vec<BenchmarkResult> = from_js(baseline, "baseline results")?;
+    let current: Vec<BenchmarkResult> = from_js(current, "current results")?;
+    let options: CompareOptions = optional_from_js(options, "compare options")?;
+    let comparison = vello_bench_core::compare_sets(&baseline, &current, &options);
+    Ok(to_js(&comparison))
+}